@@ -0,0 +1,41 @@
+use imgui::{Context, FontSource};
+use raylib::prelude::*;
+use raylib_imgui_rs::Renderer;
+use raylib_imgui_rs::docking::{DockingExt, DockSpaceOptions};
+
+fn main() {
+	let (mut rl, thread) = raylib::init()
+		.size(1280, 720)
+		.title("Docking example")
+		.resizable()
+		.build();
+
+	let mut imgui = Context::create();
+	imgui.fonts().add_font(&[FontSource::DefaultFontData { config: None }]);
+
+	let mut renderer = Renderer::create(&mut imgui, &mut rl, &thread);
+
+	while !rl.window_should_close() {
+		renderer.update(&mut imgui, &mut rl);
+
+		{
+			let ui = imgui.new_frame();
+
+			// The central node is see-through, so the raylib scene shows through it
+			ui.dockspace_over_viewport(DockSpaceOptions::default());
+
+			ui.window("Inspector").build(|| ui.text("Drag me into a corner of the window"));
+			ui.window("Log").build(|| ui.text("Or onto another window's tab bar"));
+			ui.show_demo_window(&mut true);
+		}
+
+		{
+			let mut d = rl.begin_drawing(&thread);
+
+			d.clear_background(Color::WHITE);
+			d.draw_circle(d.get_screen_width() / 2, d.get_screen_height() / 2, 64.0, Color::RED);
+
+			renderer.render(&mut imgui, &mut d);
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use imgui::{Context, FontSource};
+use raylib::prelude::*;
+use raylib_imgui_rs::Renderer;
+use raylib_imgui_rs::callback::DrawListExt;
+
+fn main() {
+	let (mut rl, thread) = raylib::init()
+		.size(640, 480)
+		.title("Draw callback example")
+		.resizable()
+		.build();
+
+	let mut imgui = Context::create();
+	imgui.fonts().add_font(&[FontSource::DefaultFontData { config: None }]);
+
+	let mut renderer = Renderer::create(&mut imgui, &mut rl, &thread);
+
+	while !rl.window_should_close() {
+		renderer.update(&mut imgui, &mut rl);
+
+		{
+			let ui = imgui.new_frame();
+
+			if let Some(_token) = ui.window("Raylib shapes").begin() {
+				let [x, y] = ui.cursor_screen_pos();
+				let time = ui.time() as f32;
+
+				// Drawn in the window's draw order, so other windows can overlap it
+				ui.get_window_draw_list().add_raylib_callback(move |d, _info| {
+					d.draw_circle((x + 64.0) as _, (y + 64.0) as _, 48.0 + time.sin() * 16.0, Color::RED);
+					d.draw_rectangle_lines((x + 8.0) as _, (y + 8.0) as _, 112, 112, Color::BLUE);
+				});
+
+				ui.dummy([128.0, 128.0]);
+			}
+
+			ui.show_demo_window(&mut true);
+		}
+
+		{
+			let mut d = rl.begin_drawing(&thread);
+
+			d.clear_background(Color::WHITE);
+			d.draw_fps(12, 12);
+
+			renderer.render(&mut imgui, &mut d);
+		}
+	}
+}
\ No newline at end of file
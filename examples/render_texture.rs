@@ -0,0 +1,43 @@
+use imgui::{Context, FontSource};
+use raylib::prelude::*;
+use raylib_imgui_rs::Renderer;
+
+fn main() {
+	let (mut rl, thread) = raylib::init()
+		.size(640, 480)
+		.title("Render texture example")
+		.resizable()
+		.build();
+
+	let mut imgui = Context::create();
+	imgui.fonts().add_font(&[FontSource::DefaultFontData { config: None }]);
+
+	let mut renderer = Renderer::create(&mut imgui, &mut rl, &thread);
+
+	// Twice the window's resolution, the ui gets scaled down when it's drawn
+	let mut target = rl.load_render_texture(&thread, 1280, 960).unwrap();
+
+	while !rl.window_should_close() {
+		renderer.update(&mut imgui, &mut rl);
+
+		imgui.new_frame().show_demo_window(&mut true);
+
+		let mut d = rl.begin_drawing(&thread);
+
+		{
+			let mut t = d.begin_texture_mode(&thread, &mut target);
+
+			t.clear_background(Color::BLANK);
+			renderer.render_to_texture(&mut imgui, &mut t);
+		}
+
+		d.clear_background(Color::DARKGRAY);
+
+		// Render textures are upside down, so flip the source rect
+		let source = Rectangle::new(0.0, 0.0, target.texture.width as _, -target.texture.height as f32);
+		let dest = Rectangle::new(0.0, 0.0, d.get_screen_width() as _, d.get_screen_height() as _);
+		d.draw_texture_pro(&target, source, dest, Vector2::zero(), 0.0, Color::WHITE);
+
+		d.draw_fps(12, 12);
+	}
+}
\ No newline at end of file
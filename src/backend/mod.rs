@@ -0,0 +1,259 @@
+mod recording;
+mod rlgl;
+
+use imgui::{DrawCmd, DrawData, DrawIdx, DrawList, DrawVert, TextureId};
+use crate::callback::CallbackInfo;
+
+pub use crate::backend::recording::{RecordingBackend, RenderCommand};
+pub use crate::backend::rlgl::{RenderMode, RenderState, RlglBackend};
+
+/// The raw imgui callback signature found in [`DrawCmd::RawCallback`]
+pub type RawCallback = unsafe extern "C" fn(*const imgui::sys::ImDrawList, *const imgui::sys::ImDrawCmd);
+
+/// Size of the frame that is about to be drawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FrameInfo {
+	/// Top left of the area imgui drew into, in imgui coordinates
+	pub display_pos: [f32; 2],
+	pub display_size: [f32; 2],
+	pub framebuffer_scale: [f32; 2],
+}
+
+impl FrameInfo {
+	pub fn framebuffer_size(&self) -> [f32; 2] {
+		[self.display_size[0] * self.framebuffer_scale[0], self.display_size[1] * self.framebuffer_scale[1]]
+	}
+}
+
+/// A clip rectangle in framebuffer pixels, with the origin at the top left corner
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ScissorRect {
+	pub x: i32,
+	pub y: i32,
+	pub width: i32,
+	pub height: i32,
+}
+
+/// One [`DrawCmd::Elements`] command, along with the buffers of the draw list it belongs to
+#[derive(Copy, Clone, Debug)]
+pub struct DrawElements<'a> {
+	pub vtx_buffer: &'a [DrawVert],
+	pub idx_buffer: &'a [DrawIdx],
+	pub count: usize,
+	pub idx_offset: usize,
+	pub vtx_offset: usize,
+	pub texture_id: TextureId,
+}
+
+impl DrawElements<'_> {
+	/// The indices of this command with `vtx_offset` already added, so they point straight into `vtx_buffer`.
+	/// These are 32 bit, since draw lists with more than 65535 vertices are split with `vtx_offset`
+	pub fn indices(&self) -> impl Iterator<Item = u32> + '_ {
+		self.idx_buffer[self.idx_offset..self.idx_offset + self.count].iter().map(|&index| self.vtx_offset as u32 + index as u32)
+	}
+
+	/// The vertices of this command in draw order, three per triangle
+	pub fn vertices(&self) -> impl Iterator<Item = &DrawVert> + '_ {
+		self.indices().map(|index| &self.vtx_buffer[index as usize])
+	}
+}
+
+/// Something that can execute the commands imgui produces.
+///
+/// [`render_draw_data`] walks the draw data and calls into this, so implementations only have to
+/// deal with scissor rectangles and triangles.
+pub trait RenderBackend {
+	fn begin_frame(&mut self, frame: &FrameInfo);
+	/// Called once before the commands of each draw list. Does nothing by default
+	fn begin_draw_list(&mut self, _vtx_buffer: &[DrawVert], _idx_buffer: &[DrawIdx]) {}
+	fn set_scissor(&mut self, rect: ScissorRect);
+	fn draw_elements(&mut self, elements: &DrawElements);
+	/// Handle [`DrawCmd::ResetRenderState`]
+	fn reset_render_state(&mut self);
+	/// Handle [`DrawCmd::RawCallback`]. The scissor in `info` has already been set
+	///
+	/// # Safety
+	/// `raw_cmd` must point to a command inside `draw_list`, which is what `callback` expects to be called with
+	unsafe fn raw_callback(&mut self, draw_list: &DrawList, callback: RawCallback, raw_cmd: *const imgui::sys::ImDrawCmd, info: &CallbackInfo);
+	fn end_frame(&mut self);
+}
+
+/// Walk through the draw data and send everything in it to the backend
+pub fn render_draw_data<B: RenderBackend>(draw_data: &DrawData, framebuffer_scale: [f32; 2], backend: &mut B) {
+	let frame = FrameInfo {
+		display_pos: draw_data.display_pos,
+		display_size: draw_data.display_size,
+		framebuffer_scale,
+	};
+
+	let [fb_width, fb_height] = frame.framebuffer_size();
+	if !(fb_width > 0.0 && fb_height > 0.0) || draw_data.draw_lists_count() == 0 {
+		return;
+	}
+
+	backend.begin_frame(&frame);
+
+	for draw_list in draw_data.draw_lists() {
+		backend.begin_draw_list(draw_list.vtx_buffer(), draw_list.idx_buffer());
+
+		for command in draw_list.commands() {
+			match command {
+				DrawCmd::Elements { count, cmd_params } => {
+					let Some(rect) = scissor_rect(cmd_params.clip_rect, draw_data.display_pos, framebuffer_scale) else {
+						continue;
+					};
+
+					backend.set_scissor(rect);
+					backend.draw_elements(&DrawElements {
+						vtx_buffer: draw_list.vtx_buffer(),
+						idx_buffer: draw_list.idx_buffer(),
+						count,
+						idx_offset: cmd_params.idx_offset,
+						vtx_offset: cmd_params.vtx_offset,
+						texture_id: cmd_params.texture_id,
+					});
+				}
+				DrawCmd::ResetRenderState => backend.reset_render_state(),
+				DrawCmd::RawCallback { callback, raw_cmd } => unsafe {
+					let clip_rect: [f32; 4] = (*raw_cmd).ClipRect.into();
+					let scissor = scissor_rect(clip_rect, draw_data.display_pos, framebuffer_scale);
+
+					if let Some(rect) = scissor {
+						backend.set_scissor(rect);
+					}
+
+					backend.raw_callback(draw_list, callback, raw_cmd, &CallbackInfo { clip_rect, scissor });
+				}
+			}
+		}
+	}
+
+	backend.end_frame();
+}
+
+/// Project an imgui clip rect into framebuffer space. Returns None if nothing would be visible
+fn scissor_rect(clip_rect: [f32; 4], display_pos: [f32; 2], framebuffer_scale: [f32; 2]) -> Option<ScissorRect> {
+	let min = [(clip_rect[0] - display_pos[0]) * framebuffer_scale[0], (clip_rect[1] - display_pos[1]) * framebuffer_scale[1]];
+	let max = [(clip_rect[2] - display_pos[0]) * framebuffer_scale[0], (clip_rect[3] - display_pos[1]) * framebuffer_scale[1]];
+
+	if max[0] <= min[0] || max[1] <= min[1] {
+		return None;
+	}
+
+	Some(ScissorRect {
+		x: min[0] as _,
+		y: min[1] as _,
+		width: (max[0] - min[0]) as _,
+		height: (max[1] - min[1]) as _,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use std::cell::Cell;
+	use std::rc::Rc;
+	use imgui::DrawList;
+	use crate::backend::{render_draw_data, DrawElements, FrameInfo, RawCallback, RecordingBackend, RenderBackend, RenderCommand, ScissorRect};
+	use crate::callback::{CallbackInfo, DrawListExt};
+	use crate::test_support::{context, ScriptedInput};
+	use crate::{Renderer, RendererConfig};
+
+	/// Keeps the highest resolved index and the vertex offsets seen
+	#[derive(Default)]
+	struct IndexBackend {
+		max_index: u32,
+		vtx_offsets: Vec<usize>,
+		out_of_bounds: bool,
+	}
+
+	impl RenderBackend for IndexBackend {
+		fn begin_frame(&mut self, _frame: &FrameInfo) {}
+
+		fn set_scissor(&mut self, _rect: ScissorRect) {}
+
+		fn draw_elements(&mut self, elements: &DrawElements) {
+			self.vtx_offsets.push(elements.vtx_offset);
+
+			for index in elements.indices() {
+				self.max_index = self.max_index.max(index);
+				self.out_of_bounds |= index as usize >= elements.vtx_buffer.len();
+			}
+		}
+
+		fn reset_render_state(&mut self) {}
+
+		unsafe fn raw_callback(&mut self, _draw_list: &DrawList, _callback: RawCallback, _raw_cmd: *const imgui::sys::ImDrawCmd, _info: &CallbackInfo) {}
+
+		fn end_frame(&mut self) {}
+	}
+
+	#[test]
+	fn draw_list_over_u16_vertices() {
+		const RECTS: usize = 20_000;
+
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		renderer.update_from(&mut ctx.imgui, &mut ScriptedInput::default());
+
+		let ui = ctx.imgui.new_frame();
+		{
+			// Unrounded filled rects are exactly 4 vertices and 2 triangles each
+			let draw_list = ui.get_background_draw_list();
+			for i in 0..RECTS {
+				let position = [(i % 200) as f32 * 4.0, (i / 200) as f32 * 4.0];
+				draw_list.add_rect(position, [position[0] + 3.0, position[1] + 3.0], [1.0, 1.0, 1.0, 1.0]).filled(true).build();
+			}
+		}
+
+		let draw_data = ctx.imgui.render();
+		let vertex_count: usize = draw_data.draw_lists().map(|draw_list| draw_list.vtx_buffer().len()).sum();
+		assert_eq!(vertex_count, RECTS * 4);
+		assert!(vertex_count > u16::MAX as usize);
+
+		let mut index_backend = IndexBackend::default();
+		render_draw_data(draw_data, [1.0, 1.0], &mut index_backend);
+
+		assert!(index_backend.max_index > u16::MAX as u32);
+		assert_eq!(index_backend.max_index as usize, vertex_count - 1);
+		assert!(!index_backend.out_of_bounds);
+		assert!(index_backend.vtx_offsets.iter().any(|&offset| offset > 0));
+
+		let mut recording_backend = RecordingBackend::new();
+		render_draw_data(draw_data, [1.0, 1.0], &mut recording_backend);
+
+		assert_eq!(recording_backend.triangle_count(), RECTS * 2);
+	}
+
+	#[test]
+	fn recording_backend_calls_callbacks() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		renderer.update_from(&mut ctx.imgui, &mut ScriptedInput::default());
+
+		let calls = Rc::new(Cell::new(0));
+		let raylib_calls = Rc::new(Cell::new(0));
+
+		let ui = ctx.imgui.new_frame();
+		{
+			let draw_list = ui.get_background_draw_list();
+			draw_list.add_rect([0.0, 0.0], [10.0, 10.0], [1.0, 1.0, 1.0, 1.0]).filled(true).build();
+
+			let calls = calls.clone();
+			draw_list.add_callback(move || calls.set(calls.get() + 1)).build();
+
+			let raylib_calls = raylib_calls.clone();
+			draw_list.add_raylib_callback(move |_, _| raylib_calls.set(raylib_calls.get() + 1));
+		}
+
+		let mut backend = RecordingBackend::new();
+		renderer.render_with(&mut ctx.imgui, &mut backend);
+
+		assert_eq!(backend.commands().iter().filter(|&command| *command == RenderCommand::RawCallback).count(), 2);
+		assert_eq!(calls.get(), 1);
+		assert_eq!(raylib_calls.get(), 0);
+
+		// Both closures were freed after being called
+		assert_eq!(Rc::strong_count(&calls), 1);
+		assert_eq!(Rc::strong_count(&raylib_calls), 1);
+	}
+}
\ No newline at end of file
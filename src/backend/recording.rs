@@ -0,0 +1,86 @@
+use imgui::{DrawList, DrawVert, TextureId};
+use crate::backend::{DrawElements, FrameInfo, RawCallback, RenderBackend, ScissorRect};
+use crate::callback::CallbackInfo;
+
+/// A single call made on a [`RecordingBackend`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum RenderCommand {
+	BeginFrame(FrameInfo),
+	SetScissor(ScissorRect),
+	/// The resolved vertices of the command, three per triangle
+	DrawTriangles { texture_id: TextureId, vertices: Vec<DrawVert> },
+	ResetRenderState,
+	/// A raw callback was called. Callbacks from [`crate::callback::DrawListExt::add_raylib_callback`]
+	/// don't draw anything, since there's no frame to draw into
+	RawCallback,
+	EndFrame,
+}
+
+/// Doesn't draw anything, just keeps a list of what would have been drawn.
+/// Useful for testing ui code on machines without a gpu
+#[derive(Clone, Debug, Default)]
+pub struct RecordingBackend {
+	commands: Vec<RenderCommand>,
+}
+
+impl RecordingBackend {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn commands(&self) -> &[RenderCommand] {
+		&self.commands
+	}
+
+	pub fn clear(&mut self) {
+		self.commands.clear();
+	}
+
+	/// Every scissor rect that was set, in order
+	pub fn scissor_rects(&self) -> Vec<ScissorRect> {
+		self.commands.iter().filter_map(|command| match command {
+			RenderCommand::SetScissor(rect) => Some(*rect),
+			_ => None,
+		}).collect()
+	}
+
+	/// The total amount of triangles drawn
+	pub fn triangle_count(&self) -> usize {
+		self.commands.iter().map(|command| match command {
+			RenderCommand::DrawTriangles { vertices, .. } => vertices.len() / 3,
+			_ => 0,
+		}).sum()
+	}
+}
+
+impl RenderBackend for RecordingBackend {
+	fn begin_frame(&mut self, frame: &FrameInfo) {
+		self.commands.push(RenderCommand::BeginFrame(*frame));
+	}
+
+	fn set_scissor(&mut self, rect: ScissorRect) {
+		self.commands.push(RenderCommand::SetScissor(rect));
+	}
+
+	fn draw_elements(&mut self, elements: &DrawElements) {
+		self.commands.push(RenderCommand::DrawTriangles {
+			texture_id: elements.texture_id,
+			vertices: elements.vertices().copied().collect(),
+		});
+	}
+
+	fn reset_render_state(&mut self) {
+		self.commands.push(RenderCommand::ResetRenderState);
+	}
+
+	unsafe fn raw_callback(&mut self, draw_list: &DrawList, callback: RawCallback, raw_cmd: *const imgui::sys::ImDrawCmd, _info: &CallbackInfo) {
+		self.commands.push(RenderCommand::RawCallback);
+
+		// Imgui-rs frees boxed closures when they're called, so they'd leak if this was skipped
+		callback(imgui::internal::RawWrapper::raw(draw_list), raw_cmd);
+	}
+
+	fn end_frame(&mut self) {
+		self.commands.push(RenderCommand::EndFrame);
+	}
+}
\ No newline at end of file
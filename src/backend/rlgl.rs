@@ -0,0 +1,331 @@
+use std::mem::{offset_of, size_of};
+use std::ptr;
+use imgui::{DrawIdx, DrawList, DrawVert};
+use raylib::consts::BlendMode;
+use raylib::ffi;
+use raylib::math::Matrix;
+use crate::backend::{DrawElements, FrameInfo, RawCallback, RenderBackend, ScissorRect};
+use crate::callback::{with_callback_info, CallbackInfo};
+
+/// How [`RlglBackend`] sends geometry to the gpu
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum RenderMode {
+	/// Every vertex goes through rlBegin/rlEnd. Slow for big uis, but works anywhere rlgl does
+	#[default]
+	Immediate,
+	/// Each draw list is uploaded into reusable vertex buffers once per frame,
+	/// and every command is drawn as an indexed range of them
+	Batched,
+}
+
+/// The rlgl state imgui draws with. [`RlglBackend`] sets one up at the start of every frame,
+/// and restores it whenever imgui asks for a [`imgui::DrawCmd::ResetRenderState`]
+#[derive(Copy, Clone, Debug)]
+pub struct RenderState {
+	pub blend_mode: BlendMode,
+	pub shader_id: u32,
+	shader_locs: *mut i32,
+	pub projection: Matrix,
+	pub modelview: Matrix,
+	/// Already in rlgl coordinates, with the origin at the bottom left
+	pub scissor: Option<ScissorRect>,
+	pub backface_culling: bool,
+}
+
+impl RenderState {
+	/// Alpha blending, the default shader, no scissor and no culling, along with the current rlgl matrices
+	pub fn imgui_default() -> Self {
+		unsafe {
+			Self {
+				blend_mode: BlendMode::BLEND_ALPHA,
+				shader_id: ffi::rlGetShaderIdDefault(),
+				shader_locs: ffi::rlGetShaderLocsDefault(),
+				projection: ffi::rlGetMatrixProjection().into(),
+				modelview: ffi::rlGetMatrixModelview().into(),
+				scissor: None,
+				backface_culling: false,
+			}
+		}
+	}
+
+	/// Like [`RenderState::imgui_default`], but with a projection that stretches the display area of the frame
+	/// over the whole framebuffer, which is what imgui expects to be drawn with
+	pub fn for_frame(frame: &FrameInfo) -> Self {
+		let [left, top] = frame.display_pos;
+		let right = left + frame.display_size[0];
+		let bottom = top + frame.display_size[1];
+
+		Self {
+			projection: Matrix::ortho(left, right, bottom, top, 0.0, 1.0),
+			modelview: Matrix::identity(),
+			..Self::imgui_default()
+		}
+	}
+
+	/// Flush anything drawn with the old state and switch to this one
+	pub fn apply(&self) {
+		unsafe {
+			ffi::rlDrawRenderBatchActive();
+
+			ffi::rlSetBlendMode(self.blend_mode as _);
+			ffi::rlSetShader(self.shader_id, self.shader_locs);
+
+			ffi::rlMatrixMode(ffi::RL_MODELVIEW as _);
+			ffi::rlSetMatrixProjection(self.projection.into());
+			ffi::rlSetMatrixModelview(self.modelview.into());
+
+			if let Some(rect) = self.scissor {
+				ffi::rlEnableScissorTest();
+				ffi::rlScissor(rect.x, rect.y, rect.width, rect.height);
+			} else {
+				ffi::rlDisableScissorTest();
+			}
+
+			if self.backface_culling {
+				ffi::rlEnableBackfaceCulling();
+			} else {
+				ffi::rlDisableBackfaceCulling();
+			}
+
+			ffi::rlSetTexture(0);
+		}
+	}
+}
+
+/// Draws through raylib's rlgl api.
+///
+/// Every frame ends with the default shader, alpha blending and backface culling on, no matter what was set before it,
+/// since rlgl has no way to report the current shader or blend mode. Set them again after rendering if the game needs them
+#[derive(Default)]
+pub struct RlglBackend {
+	mode: RenderMode,
+	framebuffer_height: i32,
+	buffers: Option<MeshBuffers>,
+	render_state: Option<RenderState>,
+	/// The rlgl matrices from before the frame started, so they can be put back afterwards
+	previous_state: Option<RenderState>,
+}
+
+impl RlglBackend {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn with_mode(mode: RenderMode) -> Self {
+		Self { mode, ..Self::default() }
+	}
+
+	pub fn mode(&self) -> RenderMode {
+		self.mode
+	}
+
+	/// Switch render modes. Switching back to [`RenderMode::Immediate`] frees the vertex buffers
+	pub fn set_mode(&mut self, mode: RenderMode) {
+		self.mode = mode;
+
+		if mode == RenderMode::Immediate {
+			self.buffers = None;
+		}
+	}
+
+	/// The state imgui is being drawn with, including the last scissor set. None outside of a frame
+	pub fn render_state(&self) -> Option<&RenderState> {
+		self.render_state.as_ref()
+	}
+
+	unsafe fn draw_immediate(&mut self, elements: &DrawElements) {
+		ffi::rlBegin(ffi::RL_TRIANGLES as _);
+		ffi::rlSetTexture(elements.texture_id.id() as _);
+
+		for vert in elements.vertices() {
+			ffi::rlColor4ub(vert.col[0], vert.col[1], vert.col[2], vert.col[3]);
+			ffi::rlTexCoord2f(vert.uv[0], vert.uv[1]);
+			ffi::rlVertex2f(vert.pos[0], vert.pos[1]);
+		}
+
+		ffi::rlEnd();
+		ffi::rlDrawRenderBatchActive();
+	}
+
+	unsafe fn draw_batched(&mut self, elements: &DrawElements) {
+		let Some(buffers) = &self.buffers else { return; };
+
+		let shader = ffi::rlGetShaderIdDefault();
+		let locs = ffi::rlGetShaderLocsDefault();
+		let loc = |index: ffi::ShaderLocationIndex| *locs.add(index as usize);
+
+		ffi::rlEnableShader(shader);
+
+		let mvp = Matrix::from(ffi::rlGetMatrixModelview()) * Matrix::from(ffi::rlGetMatrixProjection());
+		ffi::rlSetUniformMatrix(loc(ffi::ShaderLocationIndex::SHADER_LOC_MATRIX_MVP), mvp.into());
+
+		let diffuse = [1.0f32; 4];
+		ffi::rlSetUniform(loc(ffi::ShaderLocationIndex::SHADER_LOC_COLOR_DIFFUSE), diffuse.as_ptr() as _, ffi::ShaderUniformDataType::SHADER_UNIFORM_VEC4 as _, 1);
+
+		let sampler = 0i32;
+		ffi::rlActiveTextureSlot(0);
+		ffi::rlEnableTexture(elements.texture_id.id() as _);
+		ffi::rlSetUniform(loc(ffi::ShaderLocationIndex::SHADER_LOC_MAP_ALBEDO), &sampler as *const i32 as _, ffi::ShaderUniformDataType::SHADER_UNIFORM_INT as _, 1);
+
+		ffi::rlEnableVertexArray(buffers.vao);
+		ffi::rlEnableVertexBuffer(buffers.vbo);
+
+		// Point the attributes at the first vertex of this command, since rlgl has no base vertex draw call
+		let stride = size_of::<DrawVert>();
+		let base = elements.vtx_offset * stride;
+		let set_attribute = |index: ffi::ShaderLocationIndex, components, kind, normalized, offset| {
+			let location = loc(index);
+			if location < 0 { return; }
+
+			ffi::rlSetVertexAttribute(location as _, components, kind as _, normalized, stride as _, (base + offset) as _);
+			ffi::rlEnableVertexAttribute(location as _);
+		};
+
+		set_attribute(ffi::ShaderLocationIndex::SHADER_LOC_VERTEX_POSITION, 2, ffi::RL_FLOAT, false, offset_of!(DrawVert, pos));
+		set_attribute(ffi::ShaderLocationIndex::SHADER_LOC_VERTEX_TEXCOORD01, 2, ffi::RL_FLOAT, false, offset_of!(DrawVert, uv));
+		set_attribute(ffi::ShaderLocationIndex::SHADER_LOC_VERTEX_COLOR, 4, ffi::RL_UNSIGNED_BYTE, true, offset_of!(DrawVert, col));
+
+		ffi::rlEnableVertexBufferElement(buffers.ebo);
+		ffi::rlDrawVertexArrayElements(elements.idx_offset as _, elements.count as _, ptr::null());
+
+		ffi::rlDisableVertexArray();
+		ffi::rlDisableVertexBuffer();
+		ffi::rlDisableVertexBufferElement();
+		ffi::rlDisableTexture();
+		ffi::rlDisableShader();
+	}
+}
+
+impl RenderBackend for RlglBackend {
+	fn begin_frame(&mut self, frame: &FrameInfo) {
+		self.framebuffer_height = frame.framebuffer_size()[1] as _;
+
+		self.previous_state = Some(RenderState::imgui_default());
+
+		let render_state = RenderState::for_frame(frame);
+		render_state.apply();
+		self.render_state = Some(render_state);
+	}
+
+	fn begin_draw_list(&mut self, vtx_buffer: &[DrawVert], idx_buffer: &[DrawIdx]) {
+		if self.mode != RenderMode::Batched {
+			return;
+		}
+
+		unsafe {
+			self.buffers.get_or_insert_with(|| MeshBuffers::new()).upload(vtx_buffer, idx_buffer);
+		}
+	}
+
+	fn set_scissor(&mut self, rect: ScissorRect) {
+		// rlgl wants the origin at the bottom left
+		let rect = ScissorRect {
+			y: self.framebuffer_height - (rect.y + rect.height),
+			..rect
+		};
+
+		if let Some(render_state) = &mut self.render_state {
+			render_state.scissor = Some(rect);
+		}
+
+		unsafe {
+			ffi::rlEnableScissorTest();
+			ffi::rlScissor(rect.x, rect.y, rect.width, rect.height);
+		}
+	}
+
+	fn draw_elements(&mut self, elements: &DrawElements) {
+		if elements.count < 3 { return; }
+
+		unsafe {
+			match self.mode {
+				RenderMode::Immediate => self.draw_immediate(elements),
+				RenderMode::Batched => self.draw_batched(elements),
+			}
+		}
+	}
+
+	fn reset_render_state(&mut self) {
+		if let Some(render_state) = &self.render_state {
+			render_state.apply();
+		}
+	}
+
+	unsafe fn raw_callback(&mut self, draw_list: &DrawList, callback: RawCallback, raw_cmd: *const imgui::sys::ImDrawCmd, info: &CallbackInfo) {
+		ffi::rlDrawRenderBatchActive();
+
+		with_callback_info(*info, || callback(imgui::internal::RawWrapper::raw(draw_list), raw_cmd));
+
+		// Draw whatever the callback queued up while its scissor is still set, then undo its state changes
+		self.reset_render_state();
+	}
+
+	fn end_frame(&mut self) {
+		self.render_state = None;
+
+		// Backface culling is on in BeginDrawing
+		if let Some(previous_state) = self.previous_state.take() {
+			RenderState {
+				scissor: None,
+				backface_culling: true,
+				..previous_state
+			}.apply();
+		}
+	}
+}
+
+/// Gpu buffers reused between frames by [`RenderMode::Batched`]
+struct MeshBuffers {
+	vao: u32,
+	vbo: u32,
+	ebo: u32,
+	vtx_capacity: usize,
+	idx_capacity: usize,
+}
+
+impl MeshBuffers {
+	unsafe fn new() -> Self {
+		Self {
+			vao: ffi::rlLoadVertexArray(),
+			vbo: 0,
+			ebo: 0,
+			vtx_capacity: 0,
+			idx_capacity: 0,
+		}
+	}
+
+	/// Copy a draw list into the buffers, growing them if they are too small
+	unsafe fn upload(&mut self, vtx_buffer: &[DrawVert], idx_buffer: &[DrawIdx]) {
+		let vtx_size = size_of_val(vtx_buffer);
+		let idx_size = size_of_val(idx_buffer);
+
+		ffi::rlEnableVertexArray(self.vao);
+
+		if vtx_size > self.vtx_capacity {
+			ffi::rlUnloadVertexBuffer(self.vbo);
+			self.vtx_capacity = vtx_size.next_power_of_two();
+			self.vbo = ffi::rlLoadVertexBuffer(ptr::null(), self.vtx_capacity as _, true);
+		}
+
+		if idx_size > self.idx_capacity {
+			ffi::rlUnloadVertexBuffer(self.ebo);
+			self.idx_capacity = idx_size.next_power_of_two();
+			self.ebo = ffi::rlLoadVertexBufferElement(ptr::null(), self.idx_capacity as _, true);
+		}
+
+		ffi::rlUpdateVertexBuffer(self.vbo, vtx_buffer.as_ptr() as _, vtx_size as _, 0);
+		ffi::rlUpdateVertexBufferElements(self.ebo, idx_buffer.as_ptr() as _, idx_size as _, 0);
+
+		ffi::rlDisableVertexArray();
+	}
+}
+
+impl Drop for MeshBuffers {
+	fn drop(&mut self) {
+		unsafe {
+			ffi::rlUnloadVertexBuffer(self.vbo);
+			ffi::rlUnloadVertexBuffer(self.ebo);
+			ffi::rlUnloadVertexArray(self.vao);
+		}
+	}
+}
\ No newline at end of file
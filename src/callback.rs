@@ -0,0 +1,52 @@
+use std::cell::Cell;
+use imgui::DrawListMut;
+use raylib::prelude::*;
+use crate::backend::ScissorRect;
+
+thread_local! {
+	static CURRENT_CALLBACK: Cell<Option<CallbackInfo>> = const { Cell::new(None) };
+}
+
+/// Where in the frame a draw callback is running
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CallbackInfo {
+	/// The clip rect of the callback, in imgui coordinates (left, top, right, bottom)
+	pub clip_rect: [f32; 4],
+	/// The scissor active while the callback runs, in framebuffer pixels with the origin at the top left.
+	/// None if the clip rect is empty
+	pub scissor: Option<ScissorRect>,
+}
+
+/// Handed to draw callbacks. All of raylib's 2d drawing works through it,
+/// and 3d drawing works through [`RaylibMode3DExt::begin_mode3D`]
+pub struct CallbackDrawHandle {
+	_private: (),
+}
+
+impl RaylibDraw for CallbackDrawHandle {}
+
+pub trait DrawListExt {
+	/// Run `callback` when the renderer reaches this point of the draw list, with the scissor already set.
+	/// Any rlgl state the callback changes is reset once it returns
+	fn add_raylib_callback<F: FnOnce(&mut CallbackDrawHandle, &CallbackInfo) + 'static>(&self, callback: F);
+}
+
+impl DrawListExt for DrawListMut<'_> {
+	fn add_raylib_callback<F: FnOnce(&mut CallbackDrawHandle, &CallbackInfo) + 'static>(&self, callback: F) {
+		self.add_callback(move || {
+			// Only run if it's one of our renderers calling, since that's the only time there's a frame to draw into
+			if let Some(info) = CURRENT_CALLBACK.get() {
+				callback(&mut CallbackDrawHandle { _private: () }, &info);
+			}
+		}).build();
+	}
+}
+
+/// Make `info` visible to callbacks added with [`DrawListExt::add_raylib_callback`] while `f` runs
+pub(crate) fn with_callback_info<R>(info: CallbackInfo, f: impl FnOnce() -> R) -> R {
+	CURRENT_CALLBACK.set(Some(info));
+	let result = f();
+	CURRENT_CALLBACK.set(None);
+
+	result
+}
\ No newline at end of file
@@ -1,41 +1,419 @@
 use raylib::prelude::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::ffi::{CStr, CString};
+use std::rc::Rc;
 
-pub struct ClipboardBackend;
+/// What to do with text that can't cross between rust and raylib as is:
+/// nul bytes when copying, and invalid utf-8 when pasting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardPolicy {
+	/// Swap each bad part for U+FFFD
+	Replace,
+	/// Drop each bad part
+	Strip,
+	/// Keep everything before the first bad part
+	Truncate,
+	/// Don't copy or paste anything
+	Refuse,
+}
+
+/// Which way text was going when it was altered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardDirection {
+	Copy,
+	Paste,
+}
+
+/// What a [`ClipboardPolicy`] did to some text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardAlteration {
+	/// `count` bad parts were swapped for U+FFFD
+	Replaced { count: usize },
+	/// `count` bad parts were dropped
+	Stripped { count: usize },
+	/// The text was cut off at byte `at` of the original
+	Truncated { at: usize },
+	Refused,
+}
+
+type AlterationHandler = Box<dyn FnMut(ClipboardDirection, ClipboardAlteration)>;
+
+/// The system clipboard, through raylib. This is what [`crate::Renderer::create`] installs
+pub struct RaylibClipboard {
+	copy_policy: ClipboardPolicy,
+	paste_policy: ClipboardPolicy,
+	on_altered: Option<AlterationHandler>,
+}
+
+impl Default for RaylibClipboard {
+	/// Strips nul bytes when copying and replaces invalid utf-8 when pasting
+	fn default() -> Self {
+		Self {
+			copy_policy: ClipboardPolicy::Strip,
+			paste_policy: ClipboardPolicy::Replace,
+			on_altered: None,
+		}
+	}
+}
+
+impl RaylibClipboard {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// What to do with nul bytes in copied text
+	pub fn copy_policy(mut self, policy: ClipboardPolicy) -> Self {
+		self.copy_policy = policy;
+		self
+	}
+
+	/// What to do with invalid utf-8 in pasted text
+	pub fn paste_policy(mut self, policy: ClipboardPolicy) -> Self {
+		self.paste_policy = policy;
+		self
+	}
 
-impl imgui::ClipboardBackend for ClipboardBackend {
+	/// Call `handler` whenever a policy changes copied or pasted text
+	pub fn on_altered<F: FnMut(ClipboardDirection, ClipboardAlteration) + 'static>(mut self, handler: F) -> Self {
+		self.on_altered = Some(Box::new(handler));
+		self
+	}
+
+	fn report(&mut self, direction: ClipboardDirection, alteration: Option<ClipboardAlteration>) {
+		if let (Some(handler), Some(alteration)) = (&mut self.on_altered, alteration) {
+			handler(direction, alteration);
+		}
+	}
+}
+
+impl imgui::ClipboardBackend for RaylibClipboard {
 	fn get(&mut self) -> Option<String> {
-		unsafe {
+		let bytes = unsafe {
 			let text = ffi::GetClipboardText();
 
 			if text.is_null() {
 				return None;
 			}
 
-			Some(CStr::from_ptr(text).to_string_lossy().to_string())
+			CStr::from_ptr(text).to_bytes()
+		};
+
+		let (text, alteration) = decode_clipboard_text(bytes, self.paste_policy);
+		self.report(ClipboardDirection::Paste, alteration);
+		text
+	}
+
+	fn set(&mut self, value: &str) {
+		let (text, alteration) = encode_clipboard_text(value, self.copy_policy);
+		self.report(ClipboardDirection::Copy, alteration);
+
+		if let Some(text) = text {
+			unsafe {
+				ffi::SetClipboardText(text.as_ptr());
+			}
+		}
+	}
+}
+
+/// Turn `value` into a c string, using `policy` for any nul bytes. None if the policy refused it
+pub fn encode_clipboard_text(value: &str, policy: ClipboardPolicy) -> (Option<CString>, Option<ClipboardAlteration>) {
+	let count = value.bytes().filter(|&byte| byte == 0).count();
+	if count == 0 {
+		return (Some(CString::new(value).unwrap()), None);
+	}
+
+	let (text, alteration) = match policy {
+		ClipboardPolicy::Replace => (value.replace('\0', "\u{FFFD}"), ClipboardAlteration::Replaced { count }),
+		ClipboardPolicy::Strip => (value.replace('\0', ""), ClipboardAlteration::Stripped { count }),
+		ClipboardPolicy::Truncate => {
+			let at = value.find('\0').unwrap();
+			(value[..at].to_string(), ClipboardAlteration::Truncated { at })
+		}
+		ClipboardPolicy::Refuse => return (None, Some(ClipboardAlteration::Refused)),
+	};
+
+	// Every nul is gone by now
+	(Some(CString::new(text).unwrap()), Some(alteration))
+}
+
+/// Turn `bytes` into a string, using `policy` for any invalid utf-8. None if the policy refused it
+pub fn decode_clipboard_text(bytes: &[u8], policy: ClipboardPolicy) -> (Option<String>, Option<ClipboardAlteration>) {
+	if let Ok(text) = std::str::from_utf8(bytes) {
+		return (Some(text.to_string()), None);
+	}
+
+	let mut text = String::with_capacity(bytes.len());
+	let mut count = 0;
+	let mut at = 0;
+
+	for chunk in bytes.utf8_chunks() {
+		text.push_str(chunk.valid());
+		at += chunk.valid().len();
+
+		if chunk.invalid().is_empty() {
+			continue;
 		}
+
+		match policy {
+			ClipboardPolicy::Replace => text.push('\u{FFFD}'),
+			ClipboardPolicy::Strip => {}
+			ClipboardPolicy::Truncate => return (Some(text), Some(ClipboardAlteration::Truncated { at })),
+			ClipboardPolicy::Refuse => return (None, Some(ClipboardAlteration::Refused)),
+		}
+
+		count += 1;
+		at += chunk.invalid().len();
+	}
+
+	let alteration = match policy {
+		ClipboardPolicy::Replace => ClipboardAlteration::Replaced { count },
+		_ => ClipboardAlteration::Stripped { count },
+	};
+
+	(Some(text), Some(alteration))
+}
+
+/// A clipboard that only exists inside the process, for tests and headless tools.
+/// Clones share the same text, so keep one around to look at what imgui copied
+#[derive(Debug, Default, Clone)]
+pub struct MemoryClipboard {
+	text: Rc<RefCell<Option<String>>>,
+}
+
+impl MemoryClipboard {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn text(&self) -> Option<String> {
+		self.text.borrow().clone()
+	}
+
+	pub fn set_text(&self, text: Option<String>) {
+		*self.text.borrow_mut() = text;
+	}
+}
+
+impl imgui::ClipboardBackend for MemoryClipboard {
+	fn get(&mut self) -> Option<String> {
+		self.text()
 	}
 
 	fn set(&mut self, value: &str) {
-		// Try to create the CString. If value contains a null
-		let str = create_c_string(value);
-		unsafe {
-			ffi::SetClipboardText(str.as_ptr());
+		self.set_text(Some(value.to_string()));
+	}
+}
+
+/// Wraps another clipboard and remembers the last few things copied or pasted, newest first.
+/// Clones share the same history, so keep one around to build a "paste from history" menu
+pub struct ClipboardHistory<C: imgui::ClipboardBackend> {
+	inner: Rc<RefCell<HistoryInner<C>>>,
+}
+
+struct HistoryInner<C> {
+	clipboard: C,
+	entries: VecDeque<String>,
+	capacity: usize,
+}
+
+impl<C: imgui::ClipboardBackend> Clone for ClipboardHistory<C> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone() }
+	}
+}
+
+impl<C: imgui::ClipboardBackend> ClipboardHistory<C> {
+	/// Keep up to `capacity` entries of what goes through `clipboard`
+	pub fn new(clipboard: C, capacity: usize) -> Self {
+		Self {
+			inner: Rc::new(RefCell::new(HistoryInner {
+				clipboard,
+				entries: VecDeque::with_capacity(capacity),
+				capacity,
+			})),
 		}
 	}
+
+	/// Everything in the history, newest first
+	pub fn entries(&self) -> Vec<String> {
+		self.inner.borrow().entries.iter().cloned().collect()
+	}
+
+	pub fn len(&self) -> usize {
+		self.inner.borrow().entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.inner.borrow().entries.is_empty()
+	}
+
+	pub fn clear(&self) {
+		self.inner.borrow_mut().entries.clear();
+	}
+
+	/// Put entry `index` back on the clipboard, so the next paste uses it. Returns false if there's no such entry
+	pub fn select(&self, index: usize) -> bool {
+		let mut inner = self.inner.borrow_mut();
+		let Some(text) = inner.entries.get(index).cloned() else { return false };
+
+		inner.clipboard.set(&text);
+		inner.remember(text);
+		true
+	}
 }
 
-fn create_c_string(value: &str) -> CString {
-	CString::new(value).unwrap_or_else(|mut err| {
-		let mut value = value.to_string();
+impl<C> HistoryInner<C> {
+	/// Move `text` to the front, dropping the oldest entry if there's no room
+	fn remember(&mut self, text: String) {
+		if self.capacity == 0 {
+			return;
+		}
 
-		loop {
-			value.remove(err.nul_position());
+		self.entries.retain(|entry| *entry != text);
+		self.entries.truncate(self.capacity - 1);
+		self.entries.push_front(text);
+	}
+}
 
-			match CString::new(value.as_str()) {
-				Ok(str) => { return str; }
-				Err(new_err) => { err = new_err; }
-			}
+impl<C: imgui::ClipboardBackend> imgui::ClipboardBackend for ClipboardHistory<C> {
+	fn get(&mut self) -> Option<String> {
+		let mut inner = self.inner.borrow_mut();
+
+		// Text copied in other programs only shows up here, when it gets pasted
+		let text = inner.clipboard.get()?;
+		inner.remember(text.clone());
+		Some(text)
+	}
+
+	fn set(&mut self, value: &str) {
+		let mut inner = self.inner.borrow_mut();
+
+		inner.clipboard.set(value);
+		inner.remember(value.to_string());
+	}
+}
+
+/// Lets a boxed backend from [`crate::RendererConfig`] be handed to imgui
+pub(crate) struct BoxedClipboardBackend(pub Box<dyn imgui::ClipboardBackend>);
+
+impl imgui::ClipboardBackend for BoxedClipboardBackend {
+	fn get(&mut self) -> Option<String> {
+		self.0.get()
+	}
+
+	fn set(&mut self, value: &str) {
+		self.0.set(value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use imgui::ClipboardBackend;
+	use crate::clipboard::*;
+
+	fn encode(value: &str, policy: ClipboardPolicy) -> (Option<String>, Option<ClipboardAlteration>) {
+		let (text, alteration) = encode_clipboard_text(value, policy);
+		(text.map(|text| text.into_string().unwrap()), alteration)
+	}
+
+	#[test]
+	fn encode_unaltered() {
+		for policy in [ClipboardPolicy::Replace, ClipboardPolicy::Strip, ClipboardPolicy::Truncate, ClipboardPolicy::Refuse] {
+			assert_eq!(encode("héllo", policy), (Some("héllo".to_string()), None));
 		}
-	})
+	}
+
+	#[test]
+	fn encode_nul_bytes() {
+		assert_eq!(encode("a\0b\0", ClipboardPolicy::Replace), (Some("a\u{FFFD}b\u{FFFD}".to_string()), Some(ClipboardAlteration::Replaced { count: 2 })));
+		assert_eq!(encode("a\0b\0", ClipboardPolicy::Strip), (Some("ab".to_string()), Some(ClipboardAlteration::Stripped { count: 2 })));
+		assert_eq!(encode("é\0b\0", ClipboardPolicy::Truncate), (Some("é".to_string()), Some(ClipboardAlteration::Truncated { at: 2 })));
+		assert_eq!(encode("\0b", ClipboardPolicy::Truncate), (Some(String::new()), Some(ClipboardAlteration::Truncated { at: 0 })));
+		assert_eq!(encode("a\0b", ClipboardPolicy::Refuse), (None, Some(ClipboardAlteration::Refused)));
+	}
+
+	#[test]
+	fn decode_unaltered() {
+		for policy in [ClipboardPolicy::Replace, ClipboardPolicy::Strip, ClipboardPolicy::Truncate, ClipboardPolicy::Refuse] {
+			assert_eq!(decode_clipboard_text("héllo".as_bytes(), policy), (Some("héllo".to_string()), None));
+		}
+	}
+
+	#[test]
+	fn decode_invalid_utf8() {
+		let bytes = b"ab\xffcd\xfe";
+
+		assert_eq!(decode_clipboard_text(bytes, ClipboardPolicy::Replace), (Some("ab\u{FFFD}cd\u{FFFD}".to_string()), Some(ClipboardAlteration::Replaced { count: 2 })));
+		assert_eq!(decode_clipboard_text(bytes, ClipboardPolicy::Strip), (Some("abcd".to_string()), Some(ClipboardAlteration::Stripped { count: 2 })));
+		assert_eq!(decode_clipboard_text(bytes, ClipboardPolicy::Truncate), (Some("ab".to_string()), Some(ClipboardAlteration::Truncated { at: 2 })));
+		assert_eq!(decode_clipboard_text(bytes, ClipboardPolicy::Refuse), (None, Some(ClipboardAlteration::Refused)));
+	}
+
+	#[test]
+	fn decode_truncate_counts_bytes() {
+		// The euro sign is 3 bytes, and the cut off multibyte sequence is where the text stops
+		assert_eq!(decode_clipboard_text(b"\xe2\x82\xacx\xe2\x82", ClipboardPolicy::Truncate), (Some("€x".to_string()), Some(ClipboardAlteration::Truncated { at: 4 })));
+		assert_eq!(decode_clipboard_text(b"\xffabc", ClipboardPolicy::Truncate), (Some(String::new()), Some(ClipboardAlteration::Truncated { at: 0 })));
+	}
+
+	#[test]
+	fn history_keeps_newest_up_to_capacity() {
+		let mut history = ClipboardHistory::new(MemoryClipboard::new(), 2);
+
+		history.set("a");
+		history.set("b");
+		history.set("c");
+
+		assert_eq!(history.entries(), ["c", "b"]);
+	}
+
+	#[test]
+	fn history_moves_repeats_to_the_front() {
+		let mut history = ClipboardHistory::new(MemoryClipboard::new(), 3);
+
+		history.set("a");
+		history.set("b");
+		history.set("a");
+
+		assert_eq!(history.entries(), ["a", "b"]);
+	}
+
+	#[test]
+	fn history_remembers_pasted_text() {
+		let clipboard = MemoryClipboard::new();
+		let mut history = ClipboardHistory::new(clipboard.clone(), 3);
+
+		history.set("a");
+		clipboard.set_text(Some("from another program".to_string()));
+
+		assert_eq!(history.get().as_deref(), Some("from another program"));
+		assert_eq!(history.entries(), ["from another program", "a"]);
+	}
+
+	#[test]
+	fn history_select() {
+		let clipboard = MemoryClipboard::new();
+		let mut history = ClipboardHistory::new(clipboard.clone(), 3);
+
+		history.set("a");
+		history.set("b");
+
+		assert!(history.select(1));
+		assert_eq!(clipboard.text().as_deref(), Some("a"));
+		assert_eq!(history.entries(), ["a", "b"]);
+
+		assert!(!history.select(5));
+	}
+
+	#[test]
+	fn history_with_no_capacity() {
+		let clipboard = MemoryClipboard::new();
+		let mut history = ClipboardHistory::new(clipboard.clone(), 0);
+
+		history.set("a");
+
+		assert!(history.is_empty());
+		assert_eq!(clipboard.text().as_deref(), Some("a"));
+	}
 }
\ No newline at end of file
@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+use imgui::BackendFlags;
+use raylib::consts::TextureFilter;
+use crate::clipboard::RaylibClipboard;
+use crate::gamepad::GamepadConfig;
+use crate::touch::TouchConfig;
+
+/// Who gets to change the os cursor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CursorPolicy {
+	/// The renderer shows, hides and changes the shape of the os cursor to match imgui
+	#[default]
+	ImGui,
+	/// The renderer never touches the os cursor, so it isn't moved for `ConfigFlags::NAV_ENABLE_SET_MOUSE_POS` either
+	Game,
+	/// The renderer only changes the os cursor while it's over an imgui window, and resets it when it leaves
+	ImGuiWhenHovered,
+}
+
+/// Where repeats come from while a key is held
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeyRepeat {
+	/// Imgui repeats held keys itself, using `io.key_repeat_delay` and `io.key_repeat_rate`
+	#[default]
+	ImGui,
+	/// Keys repeat at the os repeat rate, like in every other text field on the desktop.
+	/// Imgui's own repeat is turned off, which also affects repeating buttons
+	Os,
+}
+
+/// Settings for [`crate::Renderer::create_with_config`]. [`RendererConfig::default`] matches [`crate::Renderer::create`]
+pub struct RendererConfig {
+	pub(crate) platform_name: Option<String>,
+	pub(crate) backend_flags: BackendFlags,
+	pub(crate) clipboard: Option<Box<dyn imgui::ClipboardBackend>>,
+	pub(crate) cursor_policy: CursorPolicy,
+	pub(crate) ini_filename: Option<Option<PathBuf>>,
+	pub(crate) gamepad: GamepadConfig,
+	pub(crate) touch: TouchConfig,
+	pub(crate) key_repeat: KeyRepeat,
+	pub(crate) font_texture_filter: TextureFilter,
+}
+
+impl Default for RendererConfig {
+	fn default() -> Self {
+		Self {
+			platform_name: Some("imgui_impl_raylib".to_string()),
+			backend_flags: BackendFlags::HAS_GAMEPAD | BackendFlags::HAS_SET_MOUSE_POS | BackendFlags::HAS_MOUSE_CURSORS | BackendFlags::RENDERER_HAS_VTX_OFFSET,
+			clipboard: Some(Box::new(RaylibClipboard::default())),
+			cursor_policy: CursorPolicy::default(),
+			ini_filename: None,
+			gamepad: GamepadConfig::default(),
+			touch: TouchConfig::default(),
+			key_repeat: KeyRepeat::default(),
+			font_texture_filter: TextureFilter::TEXTURE_FILTER_POINT,
+		}
+	}
+}
+
+impl RendererConfig {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The platform name imgui reports, for example in the metrics window
+	pub fn platform_name(mut self, name: Option<String>) -> Self {
+		self.platform_name = name;
+		self
+	}
+
+	/// The backend flags to advertise. These replace the defaults rather than adding to them
+	pub fn backend_flags(mut self, flags: BackendFlags) -> Self {
+		self.backend_flags = flags;
+		self
+	}
+
+	/// Install `clipboard` instead of raylib's clipboard, for example a [`crate::clipboard::MemoryClipboard`].
+	/// It can be swapped later with `imgui::Context::set_clipboard_backend`
+	pub fn clipboard<C: imgui::ClipboardBackend>(mut self, clipboard: C) -> Self {
+		self.clipboard = Some(Box::new(clipboard));
+		self
+	}
+
+	/// Don't install a clipboard, so imgui keeps copied text to itself
+	pub fn no_clipboard(mut self) -> Self {
+		self.clipboard = None;
+		self
+	}
+
+	pub fn cursor_policy(mut self, policy: CursorPolicy) -> Self {
+		self.cursor_policy = policy;
+		self
+	}
+
+	/// Where imgui saves window positions. None turns saving off. If this isn't called, imgui's default is kept
+	pub fn ini_filename(mut self, filename: Option<PathBuf>) -> Self {
+		self.ini_filename = Some(filename);
+		self
+	}
+
+	pub fn gamepad(mut self, gamepad: GamepadConfig) -> Self {
+		self.gamepad = gamepad;
+		self
+	}
+
+	pub fn touch(mut self, touch: TouchConfig) -> Self {
+		self.touch = touch;
+		self
+	}
+
+	pub fn key_repeat(mut self, key_repeat: KeyRepeat) -> Self {
+		self.key_repeat = key_repeat;
+		self
+	}
+
+	/// The filter the font atlas texture is sampled with. Point filtering is the default
+	pub fn font_texture_filter(mut self, filter: TextureFilter) -> Self {
+		self.font_texture_filter = filter;
+		self
+	}
+}
\ No newline at end of file
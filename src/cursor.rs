@@ -0,0 +1,61 @@
+use imgui::MouseCursor;
+use raylib::prelude::*;
+
+/// A cursor image, drawn with its hotspot over the mouse position
+pub struct ThemedCursor {
+	pub texture: Texture2D,
+	/// The pixel of the texture that points at the mouse position
+	pub hotspot: Vector2,
+}
+
+/// Textured cursors drawn by [`crate::Renderer::render`] in place of the os cursor.
+/// Imgui cursors without a texture keep using the os cursor
+pub struct CursorTheme {
+	cursors: [Option<ThemedCursor>; MouseCursor::COUNT],
+	/// How much bigger than its texture a cursor is drawn
+	pub scale: f32,
+	pub tint: Color,
+}
+
+impl Default for CursorTheme {
+	fn default() -> Self {
+		Self {
+			cursors: Default::default(),
+			scale: 1.0,
+			tint: Color::WHITE,
+		}
+	}
+}
+
+impl CursorTheme {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Draw `texture` for `cursor`, with `hotspot` over the mouse position
+	pub fn with_cursor(mut self, cursor: MouseCursor, texture: Texture2D, hotspot: Vector2) -> Self {
+		self.set(cursor, texture, hotspot);
+		self
+	}
+
+	/// Draw `texture` for `cursor`, with `hotspot` over the mouse position. Returns the cursor it replaced, if any
+	pub fn set(&mut self, cursor: MouseCursor, texture: Texture2D, hotspot: Vector2) -> Option<ThemedCursor> {
+		self.cursors[cursor as usize].replace(ThemedCursor { texture, hotspot })
+	}
+
+	/// Go back to the os cursor for `cursor`
+	pub fn remove(&mut self, cursor: MouseCursor) -> Option<ThemedCursor> {
+		self.cursors[cursor as usize].take()
+	}
+
+	pub fn get(&self, cursor: MouseCursor) -> Option<&ThemedCursor> {
+		self.cursors[cursor as usize].as_ref()
+	}
+
+	pub(crate) fn draw<D: RaylibDraw>(&self, draw: &mut D, cursor: MouseCursor, position: [f32; 2]) {
+		let Some(themed) = self.get(cursor) else { return };
+
+		let position = Vector2::new(position[0] - themed.hotspot.x * self.scale, position[1] - themed.hotspot.y * self.scale);
+		draw.draw_texture_ex(&themed.texture, position, 0.0, self.scale, self.tint);
+	}
+}
\ No newline at end of file
@@ -0,0 +1,65 @@
+use std::ptr;
+use imgui::{sys, Ui};
+
+/// Options for [`DockingExt::dockspace_over_viewport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DockSpaceOptions {
+	/// Don't draw a background in the central node, so whatever raylib drew underneath stays visible
+	pub passthru_central_node: bool,
+	/// Don't allow windows to be docked into the central node
+	pub no_docking_in_central_node: bool,
+	/// Don't allow nodes to be split
+	pub no_split: bool,
+	/// Don't allow nodes to be resized
+	pub no_resize: bool,
+	/// Hide the tab bar when a node only holds one window
+	pub auto_hide_tab_bar: bool,
+}
+
+impl Default for DockSpaceOptions {
+	fn default() -> Self {
+		Self {
+			passthru_central_node: true,
+			no_docking_in_central_node: false,
+			no_split: false,
+			no_resize: false,
+			auto_hide_tab_bar: false,
+		}
+	}
+}
+
+impl DockSpaceOptions {
+	fn flags(&self) -> sys::ImGuiDockNodeFlags {
+		let mut flags = sys::ImGuiDockNodeFlags_None;
+
+		if self.passthru_central_node {
+			flags |= sys::ImGuiDockNodeFlags_PassthruCentralNode;
+		}
+		if self.no_docking_in_central_node {
+			flags |= sys::ImGuiDockNodeFlags_NoDockingInCentralNode;
+		}
+		if self.no_split {
+			flags |= sys::ImGuiDockNodeFlags_NoSplit;
+		}
+		if self.no_resize {
+			flags |= sys::ImGuiDockNodeFlags_NoResize;
+		}
+		if self.auto_hide_tab_bar {
+			flags |= sys::ImGuiDockNodeFlags_AutoHideTabBar;
+		}
+
+		flags as _
+	}
+}
+
+/// Docking helpers for [`Ui`]
+pub trait DockingExt {
+	/// Cover the whole window with a dockspace that other windows can be docked into. Returns the dockspace's id
+	fn dockspace_over_viewport(&self, options: DockSpaceOptions) -> u32;
+}
+
+impl DockingExt for Ui {
+	fn dockspace_over_viewport(&self, options: DockSpaceOptions) -> u32 {
+		unsafe { sys::igDockSpaceOverViewport(sys::igGetMainViewport(), options.flags(), ptr::null()) }
+	}
+}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// The largest font atlas (in pixels per side) the renderer will try to upload
+pub const MAX_FONT_ATLAS_SIZE: u32 = 16384;
+
+/// Errors that can happen while setting up or updating the renderer
+#[derive(Debug)]
+pub enum Error {
+	/// Imgui built an atlas with a zero sized side, or with less pixel data than its size says it has
+	InvalidFontAtlasSize { width: u32, height: u32 },
+	/// The atlas is bigger than [`MAX_FONT_ATLAS_SIZE`] on at least one side
+	FontAtlasTooLarge { width: u32, height: u32, max: u32 },
+	/// Raylib failed to upload the atlas to the gpu
+	FontAtlasUpload { width: u32, height: u32 },
+	/// A font file couldn't be read
+	FontFile { path: PathBuf, source: std::io::Error },
+	/// Raylib couldn't read a font's texture back from the gpu
+	FontTextureRead(raylib::core::error::Error),
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Error::InvalidFontAtlasSize { width, height } => write!(f, "invalid font atlas size {width}x{height}"),
+			Error::FontAtlasTooLarge { width, height, max } => write!(f, "font atlas is {width}x{height}, but the maximum supported size is {max}x{max}"),
+			Error::FontAtlasUpload { width, height } => write!(f, "failed to upload the {width}x{height} font atlas"),
+			Error::FontFile { path, source } => write!(f, "failed to read font file {}: {source}", path.display()),
+			Error::FontTextureRead(err) => write!(f, "failed to read the font texture: {err}"),
+		}
+	}
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Error::FontFile { source, .. } => Some(source),
+			Error::FontTextureRead(err) => Some(err),
+			_ => None,
+		}
+	}
+}
\ No newline at end of file
@@ -0,0 +1,312 @@
+use std::path::Path;
+use std::rc::Rc;
+use imgui::{sys, FontAtlas, FontConfig, FontGlyphRanges, FontId, FontSource};
+use imgui::internal::RawCast;
+use raylib::prelude::*;
+use crate::error::Error;
+
+/// A ttf or otf font, and how to rasterize it
+#[derive(Clone)]
+pub struct FontFile {
+	data: Rc<[u8]>,
+	/// Passed on to imgui. The size is ignored, it comes from [`FontManager::add`] instead
+	pub config: FontConfig,
+	/// Multiplies the size the font is rasterized at. Useful for icon fonts that look too big or small next to the font they're merged into
+	pub size_scale: f32,
+}
+
+impl FontFile {
+	pub fn from_bytes(data: impl Into<Vec<u8>>) -> Self {
+		Self {
+			data: data.into().into(),
+			config: FontConfig::default(),
+			size_scale: 1.0,
+		}
+	}
+
+	pub fn from_path(path: impl AsRef<Path>) -> Result<Self, Error> {
+		let path = path.as_ref();
+		let data = std::fs::read(path).map_err(|source| Error::FontFile { path: path.to_path_buf(), source })?;
+
+		Ok(Self::from_bytes(data))
+	}
+
+	/// Only rasterize these glyphs, for example [`FontGlyphRanges::japanese`], or the range of an icon font
+	pub fn glyph_ranges(mut self, ranges: FontGlyphRanges) -> Self {
+		self.config.glyph_ranges = ranges;
+		self
+	}
+
+	pub fn size_scale(mut self, size_scale: f32) -> Self {
+		self.size_scale = size_scale;
+		self
+	}
+
+	pub fn config(mut self, config: FontConfig) -> Self {
+		self.config = config;
+		self
+	}
+
+	fn source(&self, size: f32) -> FontSource<'_> {
+		let size_pixels = size * self.size_scale;
+
+		FontSource::TtfData {
+			data: &self.data,
+			size_pixels,
+			config: Some(FontConfig { size_pixels, ..self.config.clone() }),
+		}
+	}
+}
+
+/// A raylib [`Font`], from [`RaylibHandle::load_font`] or a bmfont `.fnt` file for example, copied so it can be added
+/// to the atlas as an imgui font. Glyphs keep their pixels as is, so pixel art fonts stay sharp
+#[derive(Clone)]
+pub struct BitmapFont {
+	pixels: Rc<[Color]>,
+	width: i32,
+	height: i32,
+	glyphs: Rc<[BitmapGlyph]>,
+	base_size: f32,
+	/// Extra pixels between glyphs, like the spacing passed to raylib's text drawing functions
+	pub spacing: f32,
+}
+
+#[derive(Clone, Copy)]
+struct BitmapGlyph {
+	codepoint: u32,
+	rec: Rectangle,
+	offset: [f32; 2],
+	advance: f32,
+}
+
+impl BitmapFont {
+	/// Copy `font`, reading its texture back from the gpu. Must be called while the window is open
+	pub fn from_font<F: RaylibFont>(font: &F) -> Result<Self, Error> {
+		let image = font.texture().load_image().map_err(Error::FontTextureRead)?;
+		Ok(Self::from_image(font, &image))
+	}
+
+	/// Copy `font`, using `image` for its pixels instead of reading the texture back.
+	/// `image` should be what the texture was loaded from, like the one from `gen_image_font_atlas`
+	pub fn from_image<F: RaylibFont>(font: &F, image: &Image) -> Self {
+		let raw = font.as_ref();
+
+		// A font that failed to load has null arrays, which can't be made into slices
+		let (recs, infos): (&[ffi::Rectangle], &[ffi::GlyphInfo]) = if raw.recs.is_null() || raw.glyphs.is_null() || raw.glyphCount <= 0 {
+			(&[], &[])
+		} else {
+			unsafe { (std::slice::from_raw_parts(raw.recs, raw.glyphCount as usize), std::slice::from_raw_parts(raw.glyphs, raw.glyphCount as usize)) }
+		};
+
+		let glyphs = infos.iter().zip(recs).map(|(glyph, &rec)| BitmapGlyph {
+			codepoint: glyph.value as u32,
+			rec: rec.into(),
+			offset: [glyph.offsetX as f32, glyph.offsetY as f32],
+			// Raylib falls back to the glyph's width when it has no advance
+			advance: if glyph.advanceX == 0 { rec.width } else { glyph.advanceX as f32 },
+		}).collect();
+
+		Self {
+			pixels: image.get_image_data().to_vec().into(),
+			width: image.width,
+			height: image.height,
+			glyphs,
+			base_size: font.base_size() as f32,
+			spacing: 0.0,
+		}
+	}
+
+	pub fn spacing(mut self, spacing: f32) -> Self {
+		self.spacing = spacing;
+		self
+	}
+
+	/// The size the font was made at, which it's added to the atlas at
+	pub fn base_size(&self) -> f32 {
+		self.base_size
+	}
+
+	/// Reserve a rect in `atlas` for each glyph, belonging to `font`. Returns the rect indices, in glyph order
+	fn add_glyph_rects(&self, atlas: &mut FontAtlas, font: FontId, factor: usize) -> Vec<i32> {
+		let Some(font) = atlas.get_font(font) else { return Vec::new() };
+		let font = unsafe { font.raw() as *const sys::ImFont as *mut sys::ImFont };
+		let scale = factor as f32;
+
+		self.glyphs.iter().map(|glyph| {
+			// Imgui doesn't allow empty rects, so glyphs like space get a blank pixel
+			let width = (glyph.rec.width as usize * factor).max(1);
+			let height = (glyph.rec.height as usize * factor).max(1);
+			let offset = sys::ImVec2 { x: glyph.offset[0] * scale, y: glyph.offset[1] * scale };
+
+			unsafe {
+				sys::ImFontAtlas_AddCustomRectFontGlyph(atlas.raw_mut(), font, glyph.codepoint as _, width as _, height as _, (glyph.advance + self.spacing) * scale, offset)
+			}
+		}).collect()
+	}
+
+	/// Copy each glyph's pixels into its rect of the built atlas, scaled up `factor` times
+	fn copy_glyphs(&self, atlas: &mut FontAtlas, rects: &[i32], factor: usize) {
+		let mut pixels: *mut u8 = std::ptr::null_mut();
+		let (mut atlas_width, mut atlas_height, mut bytes_per_pixel) = (0, 0, 0);
+
+		unsafe {
+			sys::ImFontAtlas_GetTexDataAsRGBA32(atlas.raw_mut(), &mut pixels, &mut atlas_width, &mut atlas_height, &mut bytes_per_pixel);
+		}
+
+		if pixels.is_null() || bytes_per_pixel != 4 {
+			return;
+		}
+
+		let atlas_pixels = unsafe { std::slice::from_raw_parts_mut(pixels, atlas_width as usize * atlas_height as usize * 4) };
+
+		for (glyph, &index) in self.glyphs.iter().zip(rects) {
+			let rect = unsafe { &*sys::ImFontAtlas_GetCustomRectByIndex(atlas.raw_mut(), index) };
+			let (src_x, src_y) = (glyph.rec.x as i32, glyph.rec.y as i32);
+
+			for y in 0..glyph.rec.height as usize * factor {
+				for x in 0..glyph.rec.width as usize * factor {
+					let (sx, sy) = (src_x + (x / factor) as i32, src_y + (y / factor) as i32);
+					if sx < 0 || sy < 0 || sx >= self.width || sy >= self.height {
+						continue;
+					}
+
+					let (dx, dy) = (rect.X as usize + x, rect.Y as usize + y);
+					if dx >= atlas_width as usize || dy >= atlas_height as usize {
+						continue;
+					}
+
+					let color = self.pixels[(sy * self.width + sx) as usize];
+					let at = (dy * atlas_width as usize + dx) * 4;
+					atlas_pixels[at..at + 4].copy_from_slice(&[color.r, color.g, color.b, color.a]);
+				}
+			}
+		}
+	}
+}
+
+/// Refers to a font added to a [`FontManager`]. Stays valid across rebuilds, unlike an imgui [`FontId`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontHandle(usize);
+
+enum FontBase {
+	/// Imgui's built in font
+	Default,
+	File(FontFile),
+	Bitmap(BitmapFont),
+}
+
+struct FontEntry {
+	base: FontBase,
+	merged: Vec<FontFile>,
+	size: f32,
+}
+
+/// Keeps track of every font the ui uses, so the whole atlas can be rebuilt in one go.
+/// Fonts are added to the atlas in the order they're added here, so the first one is the default font.
+///
+/// Hand it to [`crate::Renderer::set_font_manager`] to have the fonts rebuilt whenever the dpi scale changes,
+/// and look them up with [`crate::Renderer::font_id`]
+#[derive(Default)]
+pub struct FontManager {
+	entries: Vec<FontEntry>,
+	ids: Vec<Option<FontId>>,
+}
+
+impl FontManager {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Add imgui's built in font at `size` pixels
+	pub fn add_default(&mut self, size: f32) -> FontHandle {
+		self.push(FontEntry { base: FontBase::Default, merged: Vec::new(), size })
+	}
+
+	/// Add `font` at `size` pixels
+	pub fn add(&mut self, font: &FontFile, size: f32) -> FontHandle {
+		self.push(FontEntry { base: FontBase::File(font.clone()), merged: Vec::new(), size })
+	}
+
+	/// Add a raylib font at its base size. Fonts merged into it fill in any glyphs it doesn't have
+	pub fn add_bitmap(&mut self, font: &BitmapFont) -> FontHandle {
+		self.push(FontEntry { base: FontBase::Bitmap(font.clone()), merged: Vec::new(), size: font.base_size })
+	}
+
+	/// Add `font` once for each of `sizes`
+	pub fn add_sizes(&mut self, font: &FontFile, sizes: &[f32]) -> Vec<FontHandle> {
+		sizes.iter().map(|&size| self.add(font, size)).collect()
+	}
+
+	/// Merge the glyphs of `font`, usually an icon font, into the font behind `handle`
+	pub fn merge(&mut self, handle: FontHandle, font: &FontFile) {
+		self.entries[handle.0].merged.push(font.clone());
+	}
+
+	/// Merge the glyphs of `font` into every font added so far, at each font's size
+	pub fn merge_into_all(&mut self, font: &FontFile) {
+		for entry in &mut self.entries {
+			entry.merged.push(font.clone());
+		}
+	}
+
+	/// The imgui font behind `handle`, as of the last build. None if it hasn't been built yet
+	pub fn font_id(&self, handle: FontHandle) -> Option<FontId> {
+		self.ids.get(handle.0).copied().flatten()
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Replace everything in `atlas` with these fonts, with sizes multiplied by `scale`.
+	/// Bitmap fonts are scaled by `scale` rounded to a whole number, so their pixels stay square.
+	///
+	/// If there are bitmap fonts, the atlas is built here so their glyphs can be copied in, and adding more fonts
+	/// afterwards would lose them. Either way the atlas still has to be uploaded
+	pub fn add_to_atlas(&mut self, atlas: &mut FontAtlas, scale: f32) {
+		atlas.clear();
+
+		let factor = scale.round().max(1.0) as usize;
+
+		self.ids = self.entries.iter().map(|entry| {
+			let size = match entry.base {
+				FontBase::Bitmap(_) => entry.size * factor as f32,
+				_ => entry.size * scale,
+			};
+
+			let mut sources = Vec::with_capacity(entry.merged.len() + 1);
+			sources.push(match &entry.base {
+				FontBase::File(font) => font.source(size),
+				FontBase::Default => FontSource::DefaultFontData { config: Some(FontConfig { size_pixels: size, ..FontConfig::default() }) },
+				// Imgui needs a real font to hang the glyphs on, so use only the space of the built in one.
+				// The bitmap font's own glyphs are added after it, and win over it
+				FontBase::Bitmap(_) => FontSource::DefaultFontData {
+					config: Some(FontConfig { size_pixels: size, glyph_ranges: FontGlyphRanges::from_slice(&[0x20, 0x20, 0]), ..FontConfig::default() }),
+				},
+			});
+			sources.extend(entry.merged.iter().map(|font| font.source(size)));
+
+			Some(atlas.add_font(&sources))
+		}).collect();
+
+		let bitmap_rects: Vec<_> = self.entries.iter().zip(&self.ids).filter_map(|(entry, id)| match &entry.base {
+			FontBase::Bitmap(font) => Some((font, font.add_glyph_rects(atlas, (*id)?, factor))),
+			_ => None,
+		}).collect();
+
+		// Imgui keeps the built pixels around, so the upload sees the copied glyphs
+		for (font, rects) in bitmap_rects {
+			font.copy_glyphs(atlas, &rects, factor);
+		}
+	}
+
+	fn push(&mut self, entry: FontEntry) -> FontHandle {
+		self.entries.push(entry);
+		self.ids.push(None);
+		FontHandle(self.entries.len() - 1)
+	}
+}
\ No newline at end of file
@@ -1,19 +1,39 @@
+use raylib::prelude::*;
+
 pub struct FrameState {
 	pub is_window_focused: bool,
+	pub is_cursor_locked: bool,
 	pub is_control_pressed: bool,
 	pub is_shift_pressed: bool,
 	pub is_alt_pressed: bool,
 	pub is_super_pressed: bool,
+	/// Keys imgui was told are down, waiting to be released
+	pub held_keys: Vec<KeyboardKey>,
+	/// Gamepad keys imgui was told are down, so they can be released once nothing maps to them anymore.
+	/// Gamepads keep working without focus, so these aren't reset
+	pub gamepad_keys: Vec<imgui::Key>,
 }
 
 impl FrameState {
-	pub fn new(raylib_handle: &mut raylib::RaylibHandle) -> Self {
+	pub fn new(is_window_focused: bool) -> Self {
 		FrameState {
-			is_window_focused: raylib_handle.is_window_focused(),
+			is_window_focused,
+			is_cursor_locked: false,
 			is_control_pressed: false,
 			is_shift_pressed: false,
 			is_alt_pressed: false,
 			is_super_pressed: false,
+			held_keys: Vec::new(),
+			gamepad_keys: Vec::new(),
 		}
 	}
+
+	/// Forget every held key and modifier
+	pub fn reset(&mut self) {
+		self.is_control_pressed = false;
+		self.is_shift_pressed = false;
+		self.is_alt_pressed = false;
+		self.is_super_pressed = false;
+		self.held_keys.clear();
+	}
 }
\ No newline at end of file
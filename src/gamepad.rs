@@ -0,0 +1,113 @@
+use imgui::Key;
+use raylib::prelude::*;
+use crate::input::InputSource;
+
+/// How many gamepads raylib keeps track of
+pub const MAX_GAMEPADS: i32 = 4;
+
+/// Which gamepads imgui reads navigation input from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GamepadSelection {
+	/// A single gamepad by index
+	Single(i32),
+	/// Several gamepads by index, merged as if they were one
+	List(Vec<i32>),
+	/// Every connected gamepad, merged as if they were one
+	AnyConnected,
+}
+
+/// Button layout presets for [`GamepadConfig::with_layout`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadLayout {
+	/// Face buttons are mapped by position, so the bottom button (A) activates and the right one (B) cancels
+	Xbox,
+	/// Same as [`GamepadLayout::Xbox`], cross activates and circle cancels
+	PlayStation,
+	/// Face buttons are mapped by label, so the right button (A) activates and the bottom one (B) cancels
+	Nintendo,
+}
+
+/// Gamepad settings for [`crate::Renderer::set_gamepad_config`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamepadConfig {
+	pub gamepads: GamepadSelection,
+	/// Which raylib button presses which imgui key. A button can appear more than once
+	pub buttons: Vec<(GamepadButton, Key)>,
+	/// Axis values closer to zero than this are ignored
+	pub left_stick_dead_zone: f32,
+	pub right_stick_dead_zone: f32,
+}
+
+impl Default for GamepadConfig {
+	fn default() -> Self {
+		Self::with_layout(GamepadLayout::Xbox)
+	}
+}
+
+impl GamepadConfig {
+	/// Read the first gamepad, using the button mapping of `layout`
+	pub fn with_layout(layout: GamepadLayout) -> Self {
+		Self {
+			gamepads: GamepadSelection::Single(0),
+			buttons: layout_buttons(layout),
+			left_stick_dead_zone: 0.2,
+			right_stick_dead_zone: 0.2,
+		}
+	}
+
+	/// Replace the button mapping with the one from `layout`
+	pub fn set_layout(&mut self, layout: GamepadLayout) {
+		self.buttons = layout_buttons(layout);
+	}
+
+	/// Point `button` at `key`, replacing whatever it was mapped to before
+	pub fn remap(&mut self, button: GamepadButton, key: Key) {
+		self.buttons.retain(|&(mapped_button, _)| mapped_button != button);
+		self.buttons.push((button, key));
+	}
+
+	/// Stop `button` from doing anything
+	pub fn unmap(&mut self, button: GamepadButton) {
+		self.buttons.retain(|&(mapped_button, _)| mapped_button != button);
+	}
+
+	/// The indices of the selected gamepads that are connected right now
+	pub fn connected_gamepads<I: InputSource>(&self, input: &I) -> Vec<i32> {
+		match &self.gamepads {
+			GamepadSelection::Single(gamepad) => vec![*gamepad],
+			GamepadSelection::List(gamepads) => gamepads.clone(),
+			GamepadSelection::AnyConnected => (0..MAX_GAMEPADS).collect(),
+		}.into_iter()
+			.filter(|&gamepad| input.is_gamepad_available(gamepad))
+			.collect()
+	}
+}
+
+fn layout_buttons(layout: GamepadLayout) -> Vec<(GamepadButton, Key)> {
+	let (face_up, face_right, face_down, face_left) = match layout {
+		GamepadLayout::Xbox | GamepadLayout::PlayStation => (Key::GamepadFaceUp, Key::GamepadFaceRight, Key::GamepadFaceDown, Key::GamepadFaceLeft),
+		GamepadLayout::Nintendo => (Key::GamepadFaceLeft, Key::GamepadFaceDown, Key::GamepadFaceRight, Key::GamepadFaceUp),
+	};
+
+	vec![
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP, Key::GamepadDpadUp),
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT, Key::GamepadDpadRight),
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN, Key::GamepadDpadDown),
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT, Key::GamepadDpadLeft),
+
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP, face_up),
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT, face_right),
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN, face_down),
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT, face_left),
+
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1, Key::GamepadL1),
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2, Key::GamepadL2),
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1, Key::GamepadR1),
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2, Key::GamepadR2),
+		(GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB, Key::GamepadL3),
+		(GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB, Key::GamepadR3),
+
+		(GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT, Key::GamepadBack),
+		(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT, Key::GamepadStart),
+	]
+}
\ No newline at end of file
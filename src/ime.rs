@@ -0,0 +1,137 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use imgui::internal::RawCast;
+use imgui::sys;
+
+type PlatformImeDataFn = unsafe extern "C" fn(*mut sys::ImGuiViewport, *mut sys::ImGuiPlatformImeData);
+type ImeHandler = Box<dyn FnMut(Option<TextInputRect>)>;
+
+/// Where imgui's text cursor is, so an ime candidate window can be placed next to it
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextInputRect {
+	/// Top left of the text cursor, in imgui coordinates
+	pub position: [f32; 2],
+	pub line_height: f32,
+}
+
+/// The ime state of one renderer
+#[derive(Default)]
+pub(crate) struct ImeState {
+	rect: Cell<Option<TextInputRect>>,
+	handler: RefCell<Option<ImeHandler>>,
+}
+
+impl ImeState {
+	/// The text input rect from the last time imgui reported one. None while no text field wants the ime
+	pub(crate) fn text_input_rect(&self) -> Option<TextInputRect> {
+		self.rect.get()
+	}
+
+	pub(crate) fn set_handler(&self, handler: Option<ImeHandler>) {
+		*self.handler.borrow_mut() = handler;
+	}
+}
+
+/// Kept in the context's `BackendPlatformUserData`, so each context reports to the renderer it was last set up by.
+///
+/// This is never freed. Imgui-rs has no way to hear about the context being destroyed, and the context can outlive
+/// the renderers that use it, so there's no point where it's known to be unused. It's only one small allocation per context,
+/// since later renderers reuse it
+struct ContextIme {
+	previous: Option<PlatformImeDataFn>,
+	state: Weak<ImeState>,
+}
+
+/// Route the ime requests of the context behind `io` to `state`. Whatever function was installed before
+/// (imgui's own win32 implementation, on windows) keeps getting called too.
+///
+/// The routing is set up once per context and is never torn down, so renderers created for the same context later
+/// just take it over. See [`ContextIme`] for why it leaks
+pub(crate) fn install(io: &mut imgui::Io, state: &Rc<ImeState>) {
+	let io = unsafe { io.raw_mut() };
+
+	let ours = set_platform_ime_data as PlatformImeDataFn as usize;
+	let is_installed = io.SetPlatformImeDataFn.is_some_and(|current| current as usize == ours) && !io.BackendPlatformUserData.is_null();
+
+	if is_installed {
+		// Keep the previous function from the first install, since the current one is this module's own
+		let context_ime = unsafe { &mut *(io.BackendPlatformUserData as *mut ContextIme) };
+		context_ime.state = Rc::downgrade(state);
+		return;
+	}
+
+	let context_ime = Box::new(ContextIme { previous: io.SetPlatformImeDataFn, state: Rc::downgrade(state) });
+	io.BackendPlatformUserData = Box::into_raw(context_ime) as _;
+	io.SetPlatformImeDataFn = Some(set_platform_ime_data);
+}
+
+/// Imgui only calls this when the data changes, at the end of the frame
+unsafe extern "C" fn set_platform_ime_data(viewport: *mut sys::ImGuiViewport, data: *mut sys::ImGuiPlatformImeData) {
+	let context_ime = (*sys::igGetIO()).BackendPlatformUserData as *const ContextIme;
+	if context_ime.is_null() {
+		return;
+	}
+
+	let context_ime = &*context_ime;
+	if let Some(previous) = context_ime.previous {
+		previous(viewport, data);
+	}
+
+	// The renderer is gone, so there's nobody to tell
+	let Some(state) = context_ime.state.upgrade() else { return };
+
+	let data = &*data;
+	let rect = data.WantVisible.then_some(TextInputRect {
+		position: [data.InputPos.x, data.InputPos.y],
+		line_height: data.InputLineHeight,
+	});
+
+	state.rect.set(rect);
+
+	// Skip the handler if this somehow happens while it's already running
+	if let Ok(mut handler) = state.handler.try_borrow_mut() {
+		if let Some(handler) = handler.as_mut() {
+			handler(rect);
+		}
+	};
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use imgui::sys;
+	use crate::ime::TextInputRect;
+	use crate::test_support::context;
+	use crate::{Renderer, RendererConfig};
+
+	static PREVIOUS_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+	unsafe extern "C" fn previous_ime_fn(_viewport: *mut sys::ImGuiViewport, _data: *mut sys::ImGuiPlatformImeData) {
+		PREVIOUS_CALLS.fetch_add(1, Ordering::SeqCst);
+	}
+
+	#[test]
+	fn recreated_renderer_chains_the_original_function() {
+		let mut ctx = context();
+		ctx.imgui.io_mut().set_platform_ime_data_fn = Some(previous_ime_fn);
+
+		let first = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let second = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+
+		let mut data = sys::ImGuiPlatformImeData {
+			WantVisible: true,
+			InputPos: sys::ImVec2 { x: 5.0, y: 6.0 },
+			InputLineHeight: 12.0,
+		};
+
+		PREVIOUS_CALLS.store(0, Ordering::SeqCst);
+		unsafe {
+			let ime_fn = ctx.imgui.io().set_platform_ime_data_fn.unwrap();
+			ime_fn(sys::igGetMainViewport(), &mut data);
+		}
+
+		assert_eq!(PREVIOUS_CALLS.load(Ordering::SeqCst), 1);
+		assert_eq!(second.text_input_rect(), Some(TextInputRect { position: [5.0, 6.0], line_height: 12.0 }));
+		assert_eq!(first.text_input_rect(), None);
+	}
+}
\ No newline at end of file
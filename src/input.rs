@@ -0,0 +1,434 @@
+use std::collections::VecDeque;
+use raylib::prelude::*;
+use crate::maps::{imgui_key, keyboard_key, MOUSE_BUTTON_MAP};
+
+/// Everything [`crate::Renderer::update_from`] reads from the platform each frame.
+///
+/// [`RaylibHandle`] implements this, but anything else (a replay file, a scripted test) can too,
+/// which together with [`crate::Renderer::headless`] makes it possible to drive imgui without a window.
+pub trait InputSource {
+	fn screen_size(&self) -> Vector2;
+	fn window_scale_dpi(&self) -> Vector2;
+	fn frame_time(&self) -> f32;
+	fn is_window_focused(&self) -> bool;
+	/// Whether the framebuffer is scaled up by the dpi scale. False by default
+	fn is_window_highdpi(&self) -> bool {
+		false
+	}
+
+	fn is_key_down(&self, key: KeyboardKey) -> bool;
+	fn is_key_pressed(&self, key: KeyboardKey) -> bool;
+	fn is_key_released(&self, key: KeyboardKey) -> bool;
+	/// Whether the os sent a repeat for a held key this frame. False by default
+	fn is_key_pressed_repeat(&self, _key: KeyboardKey) -> bool {
+		false
+	}
+	/// Pop the next key code from the pressed key queue, if any. This is the raw code,
+	/// since the queue can hold keys [`KeyboardKey`] has no variant for, like F13 or the iso `<>` key
+	fn get_key_pressed(&mut self) -> Option<u32>;
+	/// Pop the next typed character, if any
+	fn get_char_pressed(&mut self) -> Option<char>;
+
+	fn mouse_position(&self) -> Vector2;
+	fn mouse_wheel_move(&self) -> Vector2;
+	fn is_mouse_button_down(&self, button: MouseButton) -> bool;
+	fn is_mouse_button_pressed(&self, button: MouseButton) -> bool;
+	fn is_mouse_button_released(&self, button: MouseButton) -> bool;
+
+	fn is_gamepad_available(&self, gamepad: i32) -> bool;
+	fn is_gamepad_button_down(&self, gamepad: i32, button: GamepadButton) -> bool;
+	fn is_gamepad_button_pressed(&self, gamepad: i32, button: GamepadButton) -> bool;
+	fn is_gamepad_button_released(&self, gamepad: i32, button: GamepadButton) -> bool;
+	fn gamepad_axis_movement(&self, gamepad: i32, axis: GamepadAxis) -> f32;
+
+	/// How many fingers are on the touchscreen. Zero by default
+	fn touch_point_count(&self) -> u32 {
+		0
+	}
+	/// Where finger `index` is, in the same coordinates as the mouse. Zero by default
+	fn touch_position(&self, _index: u32) -> Vector2 {
+		Vector2::zero()
+	}
+	/// The gesture raylib detected this frame. None by default
+	fn gesture_detected(&self) -> Gesture {
+		Gesture::GESTURE_NONE
+	}
+	/// How far the current drag has moved since the touch started, normalized to the screen size
+	fn gesture_drag_vector(&self) -> Vector2 {
+		Vector2::zero()
+	}
+	/// The vector between the two fingers of the current pinch, normalized to the screen size
+	fn gesture_pinch_vector(&self) -> Vector2 {
+		Vector2::zero()
+	}
+	/// How long the current touch has been held, in seconds
+	fn gesture_hold_duration(&self) -> f32 {
+		0.0
+	}
+
+	/// Called when imgui wants the os cursor moved, in window coordinates (before raylib's mouse offset and scale).
+	/// Does nothing by default
+	fn set_mouse_position(&mut self, _position: Vector2) {}
+	/// Called when imgui wants the os cursor shown. Does nothing by default
+	fn show_cursor(&mut self) {}
+	/// Called when imgui wants the os cursor hidden. Does nothing by default
+	fn hide_cursor(&mut self) {}
+	/// Called when imgui wants a different os cursor shape. Does nothing by default
+	fn set_mouse_cursor(&mut self, _cursor: MouseCursor) {}
+}
+
+impl InputSource for RaylibHandle {
+	fn screen_size(&self) -> Vector2 {
+		Vector2::new(self.get_screen_width() as _, self.get_screen_height() as _)
+	}
+
+	fn window_scale_dpi(&self) -> Vector2 {
+		self.get_window_scale_dpi()
+	}
+
+	fn frame_time(&self) -> f32 {
+		self.get_frame_time()
+	}
+
+	fn is_window_focused(&self) -> bool {
+		RaylibHandle::is_window_focused(self)
+	}
+
+	fn is_window_highdpi(&self) -> bool {
+		self.get_window_state().window_highdpi()
+	}
+
+	fn is_key_down(&self, key: KeyboardKey) -> bool {
+		RaylibHandle::is_key_down(self, key)
+	}
+
+	fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+		RaylibHandle::is_key_pressed(self, key)
+	}
+
+	fn is_key_released(&self, key: KeyboardKey) -> bool {
+		RaylibHandle::is_key_released(self, key)
+	}
+
+	fn is_key_pressed_repeat(&self, key: KeyboardKey) -> bool {
+		RaylibHandle::is_key_pressed_repeat(self, key)
+	}
+
+	fn get_key_pressed(&mut self) -> Option<u32> {
+		self.get_key_pressed_number()
+	}
+
+	fn get_char_pressed(&mut self) -> Option<char> {
+		RaylibHandle::get_char_pressed(self)
+	}
+
+	fn mouse_position(&self) -> Vector2 {
+		Vector2::new(self.get_mouse_x() as _, self.get_mouse_y() as _)
+	}
+
+	fn mouse_wheel_move(&self) -> Vector2 {
+		self.get_mouse_wheel_move_v().into()
+	}
+
+	fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+		RaylibHandle::is_mouse_button_down(self, button)
+	}
+
+	fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+		RaylibHandle::is_mouse_button_pressed(self, button)
+	}
+
+	fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+		RaylibHandle::is_mouse_button_released(self, button)
+	}
+
+	fn is_gamepad_available(&self, gamepad: i32) -> bool {
+		RaylibHandle::is_gamepad_available(self, gamepad)
+	}
+
+	fn is_gamepad_button_down(&self, gamepad: i32, button: GamepadButton) -> bool {
+		RaylibHandle::is_gamepad_button_down(self, gamepad, button)
+	}
+
+	fn is_gamepad_button_pressed(&self, gamepad: i32, button: GamepadButton) -> bool {
+		RaylibHandle::is_gamepad_button_pressed(self, gamepad, button)
+	}
+
+	fn is_gamepad_button_released(&self, gamepad: i32, button: GamepadButton) -> bool {
+		RaylibHandle::is_gamepad_button_released(self, gamepad, button)
+	}
+
+	fn gamepad_axis_movement(&self, gamepad: i32, axis: GamepadAxis) -> f32 {
+		self.get_gamepad_axis_movement(gamepad, axis)
+	}
+
+	fn touch_point_count(&self) -> u32 {
+		self.get_touch_point_count()
+	}
+
+	fn touch_position(&self, index: u32) -> Vector2 {
+		self.get_touch_position(index)
+	}
+
+	fn gesture_detected(&self) -> Gesture {
+		self.get_gesture_detected()
+	}
+
+	fn gesture_drag_vector(&self) -> Vector2 {
+		self.get_gesture_drag_vector()
+	}
+
+	fn gesture_pinch_vector(&self) -> Vector2 {
+		self.get_gesture_pinch_vector()
+	}
+
+	fn gesture_hold_duration(&self) -> f32 {
+		self.get_gesture_hold_duration()
+	}
+
+	fn set_mouse_position(&mut self, position: Vector2) {
+		RaylibHandle::set_mouse_position(self, position)
+	}
+
+	fn show_cursor(&mut self) {
+		RaylibHandle::show_cursor(self)
+	}
+
+	fn hide_cursor(&mut self) {
+		RaylibHandle::hide_cursor(self)
+	}
+
+	fn set_mouse_cursor(&mut self, cursor: MouseCursor) {
+		RaylibHandle::set_mouse_cursor(self, cursor)
+	}
+}
+
+/// Raylib style input queries that report "not pressed" for anything imgui wants this frame,
+/// so clicking on a window doesn't also click in the game. See [`crate::Renderer::input_gate`]
+pub struct InputGate<'a, I: InputSource> {
+	input: &'a mut I,
+	mouse_captured: bool,
+	keyboard_captured: bool,
+	pass_through_keys: Vec<KeyboardKey>,
+	pass_through_buttons: Vec<MouseButton>,
+	/// Read by [`InputGate::get_key_pressed`] instead of the input's queue, if set
+	pressed_keys: Option<VecDeque<u32>>,
+}
+
+impl<'a, I: InputSource> InputGate<'a, I> {
+	/// Snapshot imgui's capture flags. Call this after `new_frame`, since that's when imgui updates them
+	pub fn new(imgui_context: &imgui::Context, input: &'a mut I) -> Self {
+		let io = imgui_context.io();
+
+		Self {
+			input,
+			mouse_captured: io.want_capture_mouse,
+			keyboard_captured: io.want_capture_keyboard || io.want_text_input,
+			pass_through_keys: Vec::new(),
+			pass_through_buttons: Vec::new(),
+			pressed_keys: None,
+		}
+	}
+
+	/// Force the mouse to be treated as captured or not, ignoring imgui
+	pub fn override_mouse(mut self, captured: bool) -> Self {
+		self.mouse_captured = captured;
+		self
+	}
+
+	/// Force the keyboard to be treated as captured or not, ignoring imgui
+	pub fn override_keyboard(mut self, captured: bool) -> Self {
+		self.keyboard_captured = captured;
+		self
+	}
+
+	/// Always let `key` through, even while imgui has the keyboard. Useful for things like a console toggle
+	pub fn pass_through_key(mut self, key: KeyboardKey) -> Self {
+		self.pass_through_keys.push(key);
+		self
+	}
+
+	/// Always let `button` through, even while imgui has the mouse
+	pub fn pass_through_mouse_button(mut self, button: MouseButton) -> Self {
+		self.pass_through_buttons.push(button);
+		self
+	}
+
+	/// Have [`InputGate::get_key_pressed`] go through `keys` instead of the input's own queue,
+	/// for when the queue was already drained, like by [`crate::Renderer::update`]
+	pub fn with_pressed_keys(mut self, keys: &[u32]) -> Self {
+		self.pressed_keys = Some(keys.iter().copied().collect());
+		self
+	}
+
+	pub fn is_mouse_captured(&self) -> bool {
+		self.mouse_captured
+	}
+
+	pub fn is_keyboard_captured(&self) -> bool {
+		self.keyboard_captured
+	}
+
+	/// The wrapped input, for queries that should skip the gate
+	pub fn raw(&mut self) -> &mut I {
+		self.input
+	}
+
+	/// Only keys imgui actually receives are hidden, anything else is always let through
+	fn key_open(&self, key: KeyboardKey) -> bool {
+		!self.keyboard_captured || imgui_key(key).is_none() || self.pass_through_keys.contains(&key)
+	}
+
+	/// Only buttons imgui actually receives are hidden, anything else is always let through
+	fn button_open(&self, button: MouseButton) -> bool {
+		!self.mouse_captured || !MOUSE_BUTTON_MAP.iter().any(|&(rl_button, _)| rl_button == button) || self.pass_through_buttons.contains(&button)
+	}
+
+	pub fn is_key_down(&self, key: KeyboardKey) -> bool {
+		self.key_open(key) && self.input.is_key_down(key)
+	}
+
+	pub fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+		self.key_open(key) && self.input.is_key_pressed(key)
+	}
+
+	pub fn is_key_released(&self, key: KeyboardKey) -> bool {
+		self.key_open(key) && self.input.is_key_released(key)
+	}
+
+	pub fn is_key_pressed_repeat(&self, key: KeyboardKey) -> bool {
+		self.key_open(key) && self.input.is_key_pressed_repeat(key)
+	}
+
+	/// Hidden keys count as up
+	pub fn is_key_up(&self, key: KeyboardKey) -> bool {
+		!self.is_key_down(key)
+	}
+
+	/// Pop the next raw key code, skipping keys imgui has
+	pub fn get_key_pressed(&mut self) -> Option<u32> {
+		loop {
+			let code = match &mut self.pressed_keys {
+				Some(keys) => keys.pop_front(),
+				None => self.input.get_key_pressed(),
+			}?;
+
+			if keyboard_key(code).is_none_or(|(key, _)| self.key_open(key)) {
+				return Some(code);
+			}
+		}
+	}
+
+	/// Pop the next typed character, or None while imgui has the keyboard
+	pub fn get_char_pressed(&mut self) -> Option<char> {
+		if self.keyboard_captured {
+			return None;
+		}
+
+		self.input.get_char_pressed()
+	}
+
+	/// The mouse position is always let through, only clicks and scrolling are hidden
+	pub fn mouse_position(&self) -> Vector2 {
+		self.input.mouse_position()
+	}
+
+	pub fn mouse_wheel_move(&self) -> Vector2 {
+		if self.mouse_captured {
+			return Vector2::zero();
+		}
+
+		self.input.mouse_wheel_move()
+	}
+
+	pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+		self.button_open(button) && self.input.is_mouse_button_down(button)
+	}
+
+	pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+		self.button_open(button) && self.input.is_mouse_button_pressed(button)
+	}
+
+	pub fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+		self.button_open(button) && self.input.is_mouse_button_released(button)
+	}
+
+	/// Hidden buttons count as up
+	pub fn is_mouse_button_up(&self, button: MouseButton) -> bool {
+		!self.is_mouse_button_down(button)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use raylib::prelude::*;
+	use crate::input::{InputGate, InputSource};
+	use crate::test_support::{context, ScriptedInput};
+
+	#[test]
+	fn captured_keyboard_hides_mapped_keys() {
+		let mut ctx = context();
+		ctx.imgui.io_mut().want_capture_keyboard = true;
+
+		let mut input = ScriptedInput::default();
+		input.press_key(KeyboardKey::KEY_A);
+		input.press_key(KeyboardKey::KEY_B);
+		input.char_queue.push_back('a');
+
+		let mut gate = InputGate::new(&ctx.imgui, &mut input).pass_through_key(KeyboardKey::KEY_B);
+		assert!(gate.is_keyboard_captured());
+		assert!(!gate.is_key_down(KeyboardKey::KEY_A));
+		assert!(!gate.is_key_pressed(KeyboardKey::KEY_A));
+		assert!(gate.is_key_up(KeyboardKey::KEY_A));
+		assert!(gate.is_key_down(KeyboardKey::KEY_B));
+		assert!(gate.is_key_pressed(KeyboardKey::KEY_B));
+		assert_eq!(gate.get_char_pressed(), None);
+
+		// Skipping the gate still sees everything
+		assert!(gate.raw().is_key_down(KeyboardKey::KEY_A));
+
+		let mut gate = InputGate::new(&ctx.imgui, &mut input).override_keyboard(false);
+		assert!(gate.is_key_down(KeyboardKey::KEY_A));
+		assert!(!gate.is_key_up(KeyboardKey::KEY_A));
+		assert_eq!(gate.get_char_pressed(), Some('a'));
+	}
+
+	#[test]
+	fn uncaptured_keyboard_lets_everything_through() {
+		let ctx = context();
+
+		let mut input = ScriptedInput::default();
+		input.press_key(KeyboardKey::KEY_A);
+
+		let gate = InputGate::new(&ctx.imgui, &mut input);
+		assert!(!gate.is_keyboard_captured());
+		assert!(gate.is_key_down(KeyboardKey::KEY_A));
+		assert!(gate.is_key_pressed(KeyboardKey::KEY_A));
+
+		let gate = InputGate::new(&ctx.imgui, &mut input).override_keyboard(true);
+		assert!(!gate.is_key_down(KeyboardKey::KEY_A));
+	}
+
+	#[test]
+	fn captured_mouse_hides_buttons_and_wheel() {
+		let mut ctx = context();
+		ctx.imgui.io_mut().want_capture_mouse = true;
+
+		let mut input = ScriptedInput { mouse_position: Vector2::new(5.0, 6.0), mouse_wheel: Vector2::new(0.0, 1.0), ..ScriptedInput::default() };
+		input.press_mouse_button(MouseButton::MOUSE_BUTTON_LEFT);
+		input.press_mouse_button(MouseButton::MOUSE_BUTTON_RIGHT);
+
+		let gate = InputGate::new(&ctx.imgui, &mut input).pass_through_mouse_button(MouseButton::MOUSE_BUTTON_RIGHT);
+		assert!(gate.is_mouse_captured());
+		assert!(!gate.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT));
+		assert!(!gate.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT));
+		assert!(gate.is_mouse_button_up(MouseButton::MOUSE_BUTTON_LEFT));
+		assert!(gate.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_RIGHT));
+		assert_eq!(gate.mouse_wheel_move(), Vector2::zero());
+		assert_eq!(gate.mouse_position(), Vector2::new(5.0, 6.0));
+
+		let gate = InputGate::new(&ctx.imgui, &mut input).override_mouse(false);
+		assert!(gate.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT));
+		assert_eq!(gate.mouse_wheel_move(), Vector2::new(0.0, 1.0));
+	}
+}
\ No newline at end of file
@@ -1,101 +1,365 @@
 mod frame_state;
 mod maps;
-mod clipboard;
+mod error;
+pub mod backend;
+pub mod callback;
+pub mod clipboard;
+pub mod config;
+pub mod cursor;
+#[cfg(feature = "docking")]
+pub mod docking;
+pub mod fonts;
+pub mod gamepad;
+pub mod ime;
 pub mod image;
+pub mod input;
+pub mod touch;
+#[cfg(test)]
+mod test_support;
 
 use std::ptr;
+use std::rc::Rc;
 use raylib::prelude::*;
-use imgui::{BackendFlags, ConfigFlags, DrawCmd, DrawIdx, DrawVert, Key, MouseCursor, TextureId, Ui};
-use imgui::internal::{RawCast, RawWrapper};
-use crate::clipboard::ClipboardBackend;
+use imgui::{BackendFlags, ConfigFlags, Key, MouseCursor, TextureId};
+use imgui::internal::RawCast;
+use crate::backend::{render_draw_data, RenderBackend, RlglBackend};
+use crate::clipboard::BoxedClipboardBackend;
+use crate::config::{CursorPolicy, KeyRepeat};
+use crate::cursor::CursorTheme;
+use crate::fonts::{FontHandle, FontManager};
 use crate::frame_state::FrameState;
-use crate::maps::{KEYBOARD_MAP, MOUSE_CURSOR_MAP};
+use crate::gamepad::GamepadConfig;
+use crate::ime::{ImeState, TextInputRect};
+use crate::input::{InputGate, InputSource};
+use crate::maps::{imgui_key, keyboard_key, MOUSE_BUTTON_MAP, MOUSE_CURSOR_MAP};
+use crate::touch::{TouchConfig, TouchState};
+
+pub use crate::backend::RenderMode;
+pub use crate::config::RendererConfig;
+pub use crate::error::{Error, MAX_FONT_ATLAS_SIZE};
+
+type FontBuilder = Box<dyn FnMut(&mut imgui::FontAtlas, f32)>;
 
 pub struct Renderer {
 	current_cursor: Option<MouseCursor>,
+	/// Whether the renderer was in charge of the os cursor last frame
+	owns_cursor: bool,
+	/// Set when something changed that means the os cursor has to be updated even if imgui's cursor didn't change
+	cursor_dirty: bool,
+	/// Whether the renderer hid the os cursor, so it knows to show it again when handing it back
+	cursor_hidden: bool,
+	cursor_locked: bool,
+	cursor_theme: Option<CursorTheme>,
 	last_frame_state: FrameState,
-
-	font_texture: Texture2D,
+	mouse_offset: Vector2,
+	mouse_scale: Vector2,
+	/// From the last update, for [`Renderer::render_with`]
+	is_window_highdpi: bool,
+
+	/// None for headless renderers, which never upload the atlas
+	font_texture: Option<Texture2D>,
+	font_texture_filter: TextureFilter,
+	rlgl_backend: RlglBackend,
+	gamepad_config: GamepadConfig,
+	cursor_policy: CursorPolicy,
+	touch_config: TouchConfig,
+	touch_state: TouchState,
+	key_repeat: KeyRepeat,
+	font_builder: Option<FontBuilder>,
+	/// Built instead of the font builder, if set
+	font_manager: Option<FontManager>,
+	font_scale: Option<f32>,
+	/// The last scale the font builder failed at, so it isn't retried every frame
+	failed_font_scale: Option<f32>,
+	/// The style from before the first font build, whose sizes later builds scale from
+	base_style: Option<imgui::Style>,
+	/// Imgui's repeat delay from before it was turned off for [`KeyRepeat::Os`]
+	imgui_key_repeat_delay: Option<f32>,
+	/// The raw key codes taken out of the input's queue during the last update
+	pressed_keys: Vec<u32>,
+	ime: Rc<ImeState>,
 }
 
 impl Renderer {
-	/// Create a renderer
+	/// Create a renderer. Panics if the font atlas can't be uploaded, see [`Renderer::try_create`]
 	pub fn create(imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, raylib_thread: &RaylibThread) -> Self {
-		KEYBOARD_MAP.len(); // Preload the keymap so we don't have to create it on the first frame
+		Self::try_create(imgui_context, raylib_handle, raylib_thread).unwrap_or_else(|err| panic!("{err}"))
+	}
+
+	/// Create a renderer, returning an error if the font atlas can't be uploaded
+	pub fn try_create(imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, raylib_thread: &RaylibThread) -> Result<Self, Error> {
+		Self::try_create_with_config(imgui_context, raylib_handle, raylib_thread, RendererConfig::default())
+	}
+
+	/// Create a renderer with custom settings. Panics if the font atlas can't be uploaded, see [`Renderer::try_create_with_config`]
+	pub fn create_with_config(imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, raylib_thread: &RaylibThread, config: RendererConfig) -> Self {
+		Self::try_create_with_config(imgui_context, raylib_handle, raylib_thread, config).unwrap_or_else(|err| panic!("{err}"))
+	}
+
+	/// Create a renderer with custom settings, returning an error if the font atlas can't be uploaded
+	pub fn try_create_with_config(imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, _raylib_thread: &RaylibThread, mut config: RendererConfig) -> Result<Self, Error> {
+		let ime = Self::setup_context(imgui_context, &mut config);
 
-		Self::setup_context(imgui_context);
+		let font_texture = Self::upload_font_atlas(imgui_context, config.font_texture_filter)?;
 
-		let font_texture = Self::reload_fonts_impl(imgui_context, raylib_handle, raylib_thread);
+		// Imgui's own ime implementation on windows positions the candidate window through this handle
+		unsafe {
+			(*imgui::sys::igGetMainViewport()).PlatformHandleRaw = ffi::GetWindowHandle();
+		}
 
+		Ok(Self::with_parts(config, ime, Some(font_texture), raylib_handle.is_window_focused()))
+	}
+
+	/// Create a renderer without a window, for driving imgui from an [`InputSource`] in tests or on a server.
+	///
+	/// The font atlas is built but not uploaded, so draw with [`Renderer::render_with`] instead of [`Renderer::render`].
+	/// Fonts from [`Renderer::set_font_builder`] and [`Renderer::set_font_manager`] are only built too,
+	/// but reloading the fonts by hand uploads them as usual,
+	/// which needs a window again
+	pub fn headless(imgui_context: &mut imgui::Context, mut config: RendererConfig) -> Self {
+		let ime = Self::setup_context(imgui_context, &mut config);
+		imgui_context.fonts().build_rgba32_texture();
+
+		Self::with_parts(config, ime, None, true)
+	}
+
+	fn with_parts(config: RendererConfig, ime: Rc<ImeState>, font_texture: Option<Texture2D>, is_window_focused: bool) -> Self {
 		Self {
 			current_cursor: Some(MouseCursor::Arrow),
-			last_frame_state: FrameState::new(raylib_handle),
+			owns_cursor: true,
+			cursor_dirty: false,
+			cursor_hidden: false,
+			cursor_locked: false,
+			cursor_theme: None,
+			last_frame_state: FrameState::new(is_window_focused),
+			mouse_offset: Vector2::zero(),
+			mouse_scale: Vector2::one(),
+			is_window_highdpi: false,
 
 			font_texture,
+			font_texture_filter: config.font_texture_filter,
+			rlgl_backend: RlglBackend::new(),
+			gamepad_config: config.gamepad,
+			cursor_policy: config.cursor_policy,
+			touch_config: config.touch,
+			touch_state: TouchState::default(),
+			key_repeat: config.key_repeat,
+			imgui_key_repeat_delay: None,
+			pressed_keys: Vec::new(),
+			font_builder: None,
+			font_manager: None,
+			font_scale: None,
+			failed_font_scale: None,
+			base_style: None,
+			ime,
 		}
 	}
 
-	fn setup_context(imgui_context: &mut imgui::Context) {
-		imgui_context.set_platform_name(Some("imgui_impl_raylib".to_string()));
+	fn setup_context(imgui_context: &mut imgui::Context, config: &mut RendererConfig) -> Rc<ImeState> {
+		imgui_context.set_platform_name(config.platform_name.take());
+
+		if let Some(ini_filename) = config.ini_filename.take() {
+			imgui_context.set_ini_filename(ini_filename);
+		}
 
 		let io = imgui_context.io_mut();
-		io.backend_flags.insert(BackendFlags::HAS_GAMEPAD | BackendFlags::HAS_SET_MOUSE_POS | BackendFlags::HAS_MOUSE_CURSORS);
+		io.backend_flags = config.backend_flags;
 		io.mouse_pos = [0.0, 0.0];
+		let ime = Rc::new(ImeState::default());
+		ime::install(io, &ime);
+
+		// Only the main viewport is supported, so docking is enabled but multi viewports aren't
+		#[cfg(feature = "docking")]
+		io.config_flags.insert(ConfigFlags::DOCKING_ENABLE);
 
-		imgui_context.set_clipboard_backend(ClipboardBackend);
+		if let Some(clipboard) = config.clipboard.take() {
+			imgui_context.set_clipboard_backend(BoxedClipboardBackend(clipboard));
+		}
+
+		ime
 	}
 
 	/// Update the imgui context state. Call this before new_frame()
+	///
+	/// This drains raylib's pressed key queue, so `get_key_pressed` returns nothing for the rest of the frame.
+	/// Read [`Renderer::pressed_keys`] or [`InputGate::get_key_pressed`] instead.
+	/// Panics if the font atlas has to be rebuilt and can't be uploaded, see [`Renderer::try_update`]
 	pub fn update(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle) {
-		self.update_display(imgui_context, raylib_handle);
-		self.update_mouse(imgui_context, raylib_handle);
-		self.process_events(imgui_context, raylib_handle);
+		self.update_from(imgui_context, raylib_handle);
 	}
 
-	fn update_display(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle) {
-		let resolution_scale = raylib_handle.get_window_scale_dpi();
+	/// Same as [`Renderer::update`], but returns an error if a font atlas rebuild fails
+	pub fn try_update(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle) -> Result<(), Error> {
+		self.try_update_from(imgui_context, raylib_handle)
+	}
+
+	/// Same as [`Renderer::update`], but reads input from any [`InputSource`] instead of raylib
+	pub fn update_from<I: InputSource>(&mut self, imgui_context: &mut imgui::Context, input: &mut I) {
+		self.try_update_from(imgui_context, input).unwrap_or_else(|err| panic!("{err}"));
+	}
+
+	/// Same as [`Renderer::update_from`], but returns an error if a font atlas rebuild fails
+	pub fn try_update_from<I: InputSource>(&mut self, imgui_context: &mut imgui::Context, input: &mut I) -> Result<(), Error> {
+		self.update_display(imgui_context, input);
+		self.update_mouse(imgui_context, input);
+		self.pressed_keys.clear();
+		self.process_events(imgui_context, input);
+		self.update_font_scale(imgui_context, input)
+	}
+
+	fn update_display<I: InputSource>(&mut self, imgui_context: &mut imgui::Context, input: &mut I) {
+		let resolution_scale = input.window_scale_dpi();
+		let screen_size = input.screen_size();
 
 		let io = imgui_context.io_mut();
 
-		io.display_size = [raylib_handle.get_screen_width() as _, raylib_handle.get_screen_height() as _];
+		io.display_size = [screen_size.x, screen_size.y];
 
 		io.display_framebuffer_scale = [resolution_scale.x, resolution_scale.y];
 
-		io.delta_time = raylib_handle.get_frame_time();
+		io.delta_time = input.frame_time();
+
+		self.is_window_highdpi = input.is_window_highdpi();
 	}
 
-	fn update_mouse(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle) {
+	/// Rebuild the fonts if the dpi scale changed since they were last built
+	fn update_font_scale<I: InputSource>(&mut self, imgui_context: &mut imgui::Context, input: &mut I) -> Result<(), Error> {
+		if self.font_builder.is_none() && self.font_manager.is_none() {
+			return Ok(());
+		}
+
+		let dpi_scale = input.window_scale_dpi();
+		let scale = dpi_scale.x.max(dpi_scale.y);
+		let scale = if scale > 0.0 { scale } else { 1.0 };
+
+		if self.font_scale == Some(scale) || self.failed_font_scale == Some(scale) {
+			return Ok(());
+		}
+
+		if let Err(err) = self.build_fonts(imgui_context, scale) {
+			// Don't retry every frame, only once the scale changes again
+			self.failed_font_scale = Some(scale);
+
+			// The atlas was already cleared for the new scale, so the old texture doesn't match it anymore.
+			// Go back to the last scale that worked, which the style is still scaled for
+			let fallback = self.font_scale.unwrap_or(1.0);
+			if fallback != scale && self.build_fonts(imgui_context, fallback).is_ok() {
+				self.font_scale = Some(fallback);
+			}
+
+			return Err(err);
+		}
+
+		self.font_scale = Some(scale);
+		self.failed_font_scale = None;
+
+		let base_style = *self.base_style.get_or_insert(*imgui_context.style());
+		let is_highdpi = input.is_window_highdpi();
+
+		// Only the sizes are put back, so colors and anything else changed since the first build stay as they are
+		let style = imgui_context.style_mut();
+		reset_style_sizes(style, &base_style);
+
+		// With highdpi the framebuffer is already scaled up, so the fonts just need to be drawn smaller to make up for being
+		// rasterized bigger. Without it, nothing else scales, so the whole style has to grow with the fonts
+		if is_highdpi {
+			imgui_context.io_mut().font_global_scale = 1.0 / scale;
+		} else {
+			style.scale_all_sizes(scale);
+			imgui_context.io_mut().font_global_scale = 1.0;
+		}
+
+		Ok(())
+	}
+
+	/// Clear the atlas, fill it with the font manager or builder at `scale` and upload it
+	fn build_fonts(&mut self, imgui_context: &mut imgui::Context, scale: f32) -> Result<(), Error> {
+		let fonts = imgui_context.fonts();
+
+		if let Some(font_manager) = &mut self.font_manager {
+			font_manager.add_to_atlas(fonts, scale);
+		} else if let Some(font_builder) = &mut self.font_builder {
+			fonts.clear();
+			font_builder(fonts, scale);
+		} else {
+			return Ok(());
+		}
+
+		// Headless renderers have nothing to upload to, but imgui still needs the atlas built
+		if self.font_texture.is_none() {
+			fonts.build_rgba32_texture();
+			return Ok(());
+		}
+
+		// The old texture is unloaded when it's replaced here
+		self.font_texture = Some(Self::upload_font_atlas(imgui_context, self.font_texture_filter)?);
+		Ok(())
+	}
+
+	fn update_mouse<I: InputSource>(&mut self, imgui_context: &mut imgui::Context, input: &mut I) {
 		let io = imgui_context.io();
-		if io.backend_flags.contains(BackendFlags::HAS_MOUSE_CURSORS) {
-			if !io.config_flags.contains(ConfigFlags::NO_MOUSE_CURSOR_CHANGE) {
-				let imgui_cursor = imgui_context.mouse_cursor();
-
-				if self.current_cursor != imgui_cursor || io.mouse_draw_cursor {
-					self.current_cursor = imgui_cursor;
-
-					if io.mouse_draw_cursor || imgui_cursor.is_none() {
-						raylib_handle.hide_cursor();
-					} else {
-						raylib_handle.show_cursor();
-
-						if !io.config_flags.contains(ConfigFlags::NO_MOUSE_CURSOR_CHANGE) {
-							if let Some(cursor) = imgui_cursor {
-								raylib_handle.set_mouse_cursor(MOUSE_CURSOR_MAP[cursor as usize])
-							} else {
-								raylib_handle.set_mouse_cursor(consts::MouseCursor::MOUSE_CURSOR_DEFAULT);
-							}
-						}
-					}
+
+		// Without these flags the renderer doesn't own the cursor, so render doesn't draw a themed one over the os cursor either
+		let can_change_cursor = io.backend_flags.contains(BackendFlags::HAS_MOUSE_CURSORS) && !io.config_flags.contains(ConfigFlags::NO_MOUSE_CURSOR_CHANGE);
+
+		let owns_cursor = can_change_cursor && !self.cursor_locked && match self.cursor_policy {
+			CursorPolicy::ImGui => true,
+			CursorPolicy::Game => false,
+			CursorPolicy::ImGuiWhenHovered => io.want_capture_mouse,
+		};
+
+		if !owns_cursor {
+			// Hand the cursor back the way we found it. Showing it while it's locked would unlock it, so leave it alone then
+			if self.owns_cursor && !self.cursor_locked {
+				if self.cursor_hidden {
+					input.show_cursor();
+					self.cursor_hidden = false;
 				}
+
+				input.set_mouse_cursor(consts::MouseCursor::MOUSE_CURSOR_DEFAULT);
 			}
+
+			self.owns_cursor = false;
+			return;
 		}
+
+		let imgui_cursor = imgui_context.mouse_cursor();
+
+		if !self.owns_cursor || self.cursor_dirty || self.current_cursor != imgui_cursor || io.mouse_draw_cursor {
+			self.current_cursor = imgui_cursor;
+			self.cursor_dirty = false;
+
+			// Themed cursors are drawn in render, so the os one has to get out of the way
+			let is_themed = imgui_cursor.is_some_and(|cursor| self.cursor_theme.as_ref().is_some_and(|theme| theme.get(cursor).is_some()));
+
+			if io.mouse_draw_cursor || imgui_cursor.is_none() || is_themed {
+				input.hide_cursor();
+				self.cursor_hidden = true;
+			} else {
+				input.show_cursor();
+				self.cursor_hidden = false;
+
+				if let Some(cursor) = imgui_cursor {
+					input.set_mouse_cursor(MOUSE_CURSOR_MAP[cursor as usize])
+				} else {
+					input.set_mouse_cursor(consts::MouseCursor::MOUSE_CURSOR_DEFAULT);
+				}
+			}
+		}
+
+		self.owns_cursor = true;
 	}
 
-	fn process_events(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle) {
+	fn process_events<I: InputSource>(&mut self, imgui_context: &mut imgui::Context, input: &mut I) {
 		let io = imgui_context.io_mut();
 
-		let is_window_focused = raylib_handle.is_window_focused();
+		let is_window_focused = input.is_window_focused();
 		if self.last_frame_state.is_window_focused != is_window_focused {
+			// Anything let go while unfocused never gets a release event, so release it all now
+			if !is_window_focused {
+				self.release_held_input(io);
+			}
+
 			unsafe {
 				imgui::sys::ImGuiIO_AddFocusEvent(io.raw_mut(), is_window_focused);
 			}
@@ -103,227 +367,901 @@ impl Renderer {
 			self.last_frame_state.is_window_focused = is_window_focused;
 		}
 
-		let is_control_pressed = raylib_handle.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL) | raylib_handle.is_key_down(KeyboardKey::KEY_LEFT_CONTROL);
+		// Raylib's key state goes stale while unfocused, so don't read it until focus comes back
+		if is_window_focused {
+			self.process_keyboard(io, input);
+		}
+
+		// While the game has the cursor locked, imgui shouldn't see the mouse at all
+		if self.cursor_locked {
+			if !self.last_frame_state.is_cursor_locked {
+				for &(_, button) in MOUSE_BUTTON_MAP.iter() {
+					io.add_mouse_button_event(button, false);
+				}
+
+				io.add_mouse_pos_event([-f32::MAX, -f32::MAX]);
+			}
+		} else {
+			if io.want_set_mouse_pos && self.cursor_policy != CursorPolicy::Game {
+				// Undo raylib's mouse offset and scale, since SetMousePosition works in window coordinates
+				let [x, y] = io.mouse_pos;
+				input.set_mouse_position(Vector2::new(x / self.mouse_scale.x - self.mouse_offset.x, y / self.mouse_scale.y - self.mouse_offset.y));
+			} else {
+				let mouse_position = input.mouse_position();
+				io.add_mouse_pos_event([mouse_position.x, mouse_position.y]);
+			}
+
+			for &(rl_mouse, imgui_mouse) in MOUSE_BUTTON_MAP.iter() {
+				if input.is_mouse_button_pressed(rl_mouse) {
+					io.add_mouse_button_event(imgui_mouse, true);
+				} else if input.is_mouse_button_released(rl_mouse) {
+					io.add_mouse_button_event(imgui_mouse, false);
+				}
+			}
+
+			let mouse_wheel = input.mouse_wheel_move();
+			io.add_mouse_wheel_event([mouse_wheel.x, mouse_wheel.y]);
+		}
+
+		self.last_frame_state.is_cursor_locked = self.cursor_locked;
+
+		if !self.cursor_locked {
+			self.touch_state.process(&self.touch_config, io, input);
+		}
+
+		if io.config_flags.contains(ConfigFlags::NAV_ENABLE_GAMEPAD) {
+			let config = &self.gamepad_config;
+			let gamepads = config.connected_gamepads(input);
+
+			// Several buttons can map to the same key, and several gamepads can hold the same button,
+			// so work out whether each key is down before telling imgui about it
+			let mut key_states: Vec<(Key, bool)> = Vec::with_capacity(config.buttons.len());
+			for &(button, key) in &config.buttons {
+				let is_down = gamepads.iter().any(|&gamepad| input.is_gamepad_button_down(gamepad, button));
+
+				match key_states.iter_mut().find(|(state_key, _)| *state_key == key) {
+					Some((_, key_down)) => *key_down |= is_down,
+					None => key_states.push((key, is_down)),
+				}
+			}
+
+			// Keys that were down but lost their button to a remap would otherwise stay down forever
+			for &key in &self.last_frame_state.gamepad_keys {
+				if !key_states.iter().any(|&(state_key, _)| state_key == key) {
+					key_states.push((key, false));
+				}
+			}
+
+			self.last_frame_state.gamepad_keys = key_states.iter().filter(|&&(_, is_down)| is_down).map(|&(key, _)| key).collect();
+
+			for (key, is_down) in key_states {
+				io.add_key_event(key, is_down);
+			}
+
+			let mut handle_gamepad_stick_event = |axis, dead_zone: f32, neg_key, pos_key| {
+				// Use whichever gamepad has the stick pushed the furthest
+				let axis_value = gamepads.iter()
+					.map(|&gamepad| input.gamepad_axis_movement(gamepad, axis))
+					.fold(0.0f32, |furthest, value| if value.abs() > furthest.abs() { value } else { furthest });
+
+				io.add_key_analog_event(neg_key, axis_value < -dead_zone, if axis_value < -dead_zone { -axis_value } else { 0.0 });
+				io.add_key_analog_event(pos_key, axis_value > dead_zone, if axis_value > dead_zone { axis_value } else { 0.0 });
+			};
+
+			// left stick
+			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_LEFT_X, config.left_stick_dead_zone, Key::GamepadLStickLeft, Key::GamepadLStickRight);
+			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_LEFT_Y, config.left_stick_dead_zone, Key::GamepadLStickUp, Key::GamepadLStickDown);
+
+			// right stick
+			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_RIGHT_X, config.right_stick_dead_zone, Key::GamepadRStickLeft, Key::GamepadRStickRight);
+			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_RIGHT_Y, config.right_stick_dead_zone, Key::GamepadRStickUp, Key::GamepadRStickDown);
+		}
+	}
+
+	fn process_keyboard<I: InputSource>(&mut self, io: &mut imgui::Io, input: &mut I) {
+		let is_control_pressed = input.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL) | input.is_key_down(KeyboardKey::KEY_LEFT_CONTROL);
 		if self.last_frame_state.is_control_pressed != is_control_pressed {
 			io.add_key_event(Key::ModCtrl, is_control_pressed);
 			self.last_frame_state.is_control_pressed = is_control_pressed;
 		}
 
-		let is_shift_pressed = raylib_handle.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT) | raylib_handle.is_key_down(KeyboardKey::KEY_LEFT_SHIFT);
+		let is_shift_pressed = input.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT) | input.is_key_down(KeyboardKey::KEY_LEFT_SHIFT);
 		if self.last_frame_state.is_shift_pressed != is_shift_pressed {
 			io.add_key_event(Key::ModShift, is_shift_pressed);
 			self.last_frame_state.is_shift_pressed = is_shift_pressed;
 		}
 
-		let is_alt_pressed = raylib_handle.is_key_down(KeyboardKey::KEY_RIGHT_ALT) | raylib_handle.is_key_down(KeyboardKey::KEY_LEFT_ALT);
+		let is_alt_pressed = input.is_key_down(KeyboardKey::KEY_RIGHT_ALT) | input.is_key_down(KeyboardKey::KEY_LEFT_ALT);
 		if self.last_frame_state.is_alt_pressed != is_alt_pressed {
 			io.add_key_event(Key::ModAlt, is_alt_pressed);
 			self.last_frame_state.is_alt_pressed = is_alt_pressed;
 		}
 
-		let is_super_pressed = raylib_handle.is_key_down(KeyboardKey::KEY_RIGHT_SUPER) | raylib_handle.is_key_down(KeyboardKey::KEY_LEFT_SUPER);
+		let is_super_pressed = input.is_key_down(KeyboardKey::KEY_RIGHT_SUPER) | input.is_key_down(KeyboardKey::KEY_LEFT_SUPER);
 		if self.last_frame_state.is_super_pressed != is_super_pressed {
 			io.add_key_event(Key::ModSuper, is_super_pressed);
 			self.last_frame_state.is_super_pressed = is_super_pressed;
 		}
 
-		for (&rl_key, &imgui_key) in KEYBOARD_MAP.iter() {
-			if raylib_handle.is_key_released(rl_key) {
-				io.add_key_event(imgui_key, false);
-			} else if raylib_handle.is_key_pressed(rl_key) {
-				io.add_key_event(imgui_key, true);
+		// Only keys that were pressed get looked at, and they're only checked for release until they're let go
+		while let Some(code) = input.get_key_pressed() {
+			self.pressed_keys.push(code);
+
+			// Keys without a mapping still have to be popped, or everything queued after them would be lost
+			let Some((rl_key, key)) = keyboard_key(code) else { continue };
+
+			io.add_key_event(key, true);
+			if !self.last_frame_state.held_keys.contains(&rl_key) {
+				self.last_frame_state.held_keys.push(rl_key);
 			}
 		}
 
+		// Turn imgui's own repeat off while the os is repeating, and put it back afterwards
+		if self.key_repeat == KeyRepeat::Os && self.imgui_key_repeat_delay.is_none() {
+			self.imgui_key_repeat_delay = Some(io.key_repeat_delay);
+			io.key_repeat_delay = f32::MAX;
+		} else if self.key_repeat == KeyRepeat::ImGui {
+			if let Some(delay) = self.imgui_key_repeat_delay.take() {
+				io.key_repeat_delay = delay;
+			}
+		}
+
+		let os_repeat = self.key_repeat == KeyRepeat::Os;
+		self.last_frame_state.held_keys.retain(|&rl_key| {
+			let is_down = input.is_key_down(rl_key);
+			let Some(key) = imgui_key(rl_key) else { return is_down };
+
+			if !is_down {
+				io.add_key_event(key, false);
+			} else if os_repeat && input.is_key_pressed_repeat(rl_key) {
+				// Imgui has no repeat event, so let go and press again
+				io.add_key_event(key, false);
+				io.add_key_event(key, true);
+			}
+
+			is_down
+		});
+
 		if io.want_capture_keyboard {
-			while let Some(pressed) = raylib_handle.get_char_pressed() {
+			while let Some(pressed) = input.get_char_pressed() {
 				io.add_input_character(pressed);
 			}
 		}
+	}
 
-		if!io.want_set_mouse_pos {
-			io.add_mouse_pos_event([raylib_handle.get_mouse_x() as _, raylib_handle.get_mouse_y() as _]);
+	/// Send a release for every key, modifier and mouse button imgui might think is down, and forget about them
+	fn release_held_input(&mut self, io: &mut imgui::Io) {
+		for rl_key in self.last_frame_state.held_keys.drain(..) {
+			if let Some(key) = imgui_key(rl_key) {
+				io.add_key_event(key, false);
+			}
 		}
 
-		let mut set_mouse_event = |rl_mouse, imgui_mouse| {
-			if raylib_handle.is_mouse_button_pressed(rl_mouse) {
-				io.add_mouse_button_event(imgui_mouse, true);
-			} else if raylib_handle.is_mouse_button_released(rl_mouse) {
-				io.add_mouse_button_event(imgui_mouse, false);
+		for key in [Key::ModCtrl, Key::ModShift, Key::ModAlt, Key::ModSuper] {
+			io.add_key_event(key, false);
+		}
+
+		for &(_, button) in MOUSE_BUTTON_MAP.iter() {
+			io.add_mouse_button_event(button, false);
+		}
+
+		self.last_frame_state.reset();
+	}
+
+	/// Render the frame. Call this after drawing all your imgui stuff.
+	pub fn render(&mut self, imgui_context: &mut imgui::Context, draw: &mut RaylibDrawHandle) {
+		let draw_data = imgui_context.render();
+		render_draw_data(draw_data, framebuffer_scale(draw_data, draw.get_window_state().window_highdpi()), &mut self.rlgl_backend);
+
+		// The themed cursor goes on top of everything
+		if let (Some(theme), Some(cursor)) = (&self.cursor_theme, imgui_context.mouse_cursor()) {
+			if self.owns_cursor && !imgui_context.io().mouse_draw_cursor {
+				theme.draw(draw, cursor, imgui_context.io().mouse_pos);
 			}
-		};
+		}
+	}
 
-		set_mouse_event(MouseButton::MOUSE_BUTTON_LEFT, imgui::MouseButton::Left);
-		set_mouse_event(MouseButton::MOUSE_BUTTON_RIGHT, imgui::MouseButton::Right);
-		set_mouse_event(MouseButton::MOUSE_BUTTON_MIDDLE, imgui::MouseButton::Middle);
-		set_mouse_event(MouseButton::MOUSE_BUTTON_FORWARD, imgui::MouseButton::Extra1);
-		set_mouse_event(MouseButton::MOUSE_BUTTON_BACK, imgui::MouseButton::Extra2);
-
-		let mouse_wheel = raylib_handle.get_mouse_wheel_move_v();
-		io.add_mouse_wheel_event([mouse_wheel.x, mouse_wheel.y]);
-
-		if io.config_flags.contains(ConfigFlags::NAV_ENABLE_GAMEPAD) && raylib_handle.is_gamepad_available(0) {
-			let mut handle_gamepad_button_event = |rl_button, imgui_button| {
-				if raylib_handle.is_gamepad_button_pressed(0, rl_button) {
-					io.add_key_event(imgui_button, true);
-				} else if raylib_handle.is_gamepad_button_released(0, rl_button) {
-					io.add_key_event(imgui_button, false);
-				}
-			};
+	/// Render the frame into a render texture instead of the window.
+	/// The ui is stretched over the whole texture, so it's drawn at the texture's resolution
+	pub fn render_to_texture<T>(&mut self, imgui_context: &mut imgui::Context, _target: &mut RaylibTextureMode<T>) {
+		// BeginTextureMode sets the framebuffer size to the size of the texture
+		let (width, height) = unsafe { (ffi::rlGetFramebufferWidth(), ffi::rlGetFramebufferHeight()) };
 
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP, Key::GamepadDpadUp);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT, Key::GamepadDpadRight);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN, Key::GamepadDpadDown);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT, Key::GamepadDpadLeft);
+		let draw_data = imgui_context.render();
+		let [display_width, display_height] = draw_data.display_size;
+		if display_width <= 0.0 || display_height <= 0.0 {
+			return;
+		}
 
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP, Key::GamepadFaceUp);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT, Key::GamepadFaceLeft);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN, Key::GamepadFaceDown);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT, Key::GamepadFaceRight);
+		let framebuffer_scale = [width as f32 / display_width, height as f32 / display_height];
+		render_draw_data(draw_data, framebuffer_scale, &mut self.rlgl_backend);
+	}
 
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1, Key::GamepadL1);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2, Key::GamepadL2);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1, Key::GamepadR1);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2, Key::GamepadR2);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB, Key::GamepadL3);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB, Key::GamepadR3);
+	/// Wrap `input` so the game only sees input imgui doesn't want. Call this after `new_frame`.
+	/// The gate's [`InputGate::get_key_pressed`] reads the keys from [`Renderer::pressed_keys`]
+	pub fn input_gate<'a, I: InputSource>(&self, imgui_context: &imgui::Context, input: &'a mut I) -> InputGate<'a, I> {
+		InputGate::new(imgui_context, input).with_pressed_keys(&self.pressed_keys)
+	}
 
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT, Key::GamepadStart);
-			handle_gamepad_button_event(GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT, Key::GamepadBack);
+	/// The raw key codes the last update took out of the pressed key queue, in the order they were pressed.
+	/// Empty while the window isn't focused, since the queue is left alone then
+	pub fn pressed_keys(&self) -> &[u32] {
+		&self.pressed_keys
+	}
 
-			let mut handle_gamepad_stick_event = |axis, neg_key, pos_key| {
-				const DEAD_ZONE: f32 = 0.2;
+	/// Tell the renderer about the offset and scale passed to raylib's `set_mouse_offset` and `set_mouse_scale`,
+	/// so the cursor lands in the right place when imgui moves it (see [`ConfigFlags::NAV_ENABLE_SET_MOUSE_POS`])
+	pub fn set_mouse_transform(&mut self, offset: Vector2, scale: Vector2) {
+		self.mouse_offset = offset;
+		self.mouse_scale = scale;
+	}
 
-				let axis_value = raylib_handle.get_gamepad_axis_movement(0, axis);
+	pub fn cursor_policy(&self) -> CursorPolicy {
+		self.cursor_policy
+	}
 
-				io.add_key_analog_event(neg_key, axis_value < -DEAD_ZONE, if axis_value < -DEAD_ZONE { -axis_value } else { 0.0 });
-				io.add_key_analog_event(pos_key, axis_value > DEAD_ZONE, if axis_value > DEAD_ZONE { axis_value } else { 0.0 });
-			};
+	/// Choose who gets to change the os cursor
+	pub fn set_cursor_policy(&mut self, policy: CursorPolicy) {
+		self.cursor_policy = policy;
+		self.cursor_dirty = true;
+	}
 
-			// left stick
-			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_LEFT_X, Key::GamepadLStickLeft, Key::GamepadLStickRight);
-			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_LEFT_Y, Key::GamepadLStickUp, Key::GamepadLStickDown);
+	pub fn cursor_theme(&self) -> Option<&CursorTheme> {
+		self.cursor_theme.as_ref()
+	}
 
-			// right stick
-			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_RIGHT_X, Key::GamepadRStickLeft, Key::GamepadRStickRight);
-			handle_gamepad_stick_event(GamepadAxis::GAMEPAD_AXIS_RIGHT_Y, Key::GamepadRStickUp, Key::GamepadRStickDown);
-		}
+	pub fn cursor_theme_mut(&mut self) -> Option<&mut CursorTheme> {
+		self.cursor_dirty = true;
+		self.cursor_theme.as_mut()
 	}
 
-	/// Render the frame. Call this after drawing all your imgui stuff.
-	pub fn render(&self, imgui_context: &mut imgui::Context, draw: &mut RaylibDrawHandle) {
-		let io = imgui_context.io();
-		
-		let display_framebuffer_scale = if draw.get_window_state().window_highdpi() {
-			io.display_framebuffer_scale
-		} else { 
-			[1.0, 1.0]
-		};
-		
-		let display_size = io.display_size;
+	/// Draw textured cursors at the end of [`Renderer::render`] instead of using the os cursor. Returns the previous theme
+	pub fn set_cursor_theme(&mut self, theme: Option<CursorTheme>) -> Option<CursorTheme> {
+		self.cursor_dirty = true;
+		std::mem::replace(&mut self.cursor_theme, theme)
+	}
+
+	/// Tell the renderer the game has locked the cursor, for example with raylib's `disable_cursor`.
+	/// While it's locked the renderer doesn't touch the cursor, and imgui gets no mouse input
+	pub fn set_cursor_locked(&mut self, locked: bool) {
+		self.cursor_locked = locked;
+	}
+
+	pub fn is_cursor_locked(&self) -> bool {
+		self.cursor_locked
+	}
+
+	pub fn key_repeat(&self) -> KeyRepeat {
+		self.key_repeat
+	}
+
+	/// Choose whether held keys repeat at the os rate or at imgui's rate
+	pub fn set_key_repeat(&mut self, key_repeat: KeyRepeat) {
+		self.key_repeat = key_repeat;
+	}
+
+	/// Where the text cursor of the active text field is, for placing an ime candidate window.
+	/// None while no text field wants the ime. This is updated when the frame is rendered
+	pub fn text_input_rect(&self) -> Option<TextInputRect> {
+		self.ime.text_input_rect()
+	}
+
+	/// Call `handler` whenever [`Renderer::text_input_rect`] changes, to forward it to the platform's ime
+	pub fn set_ime_handler<F: FnMut(Option<TextInputRect>) + 'static>(&mut self, handler: F) {
+		self.ime.set_handler(Some(Box::new(handler)));
+	}
+
+	pub fn clear_ime_handler(&mut self) {
+		self.ime.set_handler(None);
+	}
+
+	pub fn touch_config(&self) -> &TouchConfig {
+		&self.touch_config
+	}
+
+	/// Choose how touch points and gestures are turned into imgui input. Everything is off by default
+	pub fn set_touch_config(&mut self, config: TouchConfig) {
+		self.touch_config = config;
+	}
+
+	pub fn gamepad_config(&self) -> &GamepadConfig {
+		&self.gamepad_config
+	}
+
+	/// Choose which gamepads drive imgui's gamepad navigation, and how their buttons are mapped.
+	/// Navigation also needs [`ConfigFlags::NAV_ENABLE_GAMEPAD`] set on the context
+	pub fn set_gamepad_config(&mut self, config: GamepadConfig) {
+		self.gamepad_config = config;
+	}
+
+	pub fn render_mode(&self) -> RenderMode {
+		self.rlgl_backend.mode()
+	}
+
+	/// Choose how [`Renderer::render`] sends geometry to the gpu. [`RenderMode::Immediate`] is the default
+	pub fn set_render_mode(&mut self, mode: RenderMode) {
+		self.rlgl_backend.set_mode(mode);
+	}
+
+	/// Render the frame through any [`RenderBackend`], for example a [`backend::RecordingBackend`] in tests.
+	/// The framebuffer scale is picked like in [`Renderer::render`], going by whether the window was highdpi on the last update
+	pub fn render_with<B: RenderBackend>(&self, imgui_context: &mut imgui::Context, backend: &mut B) {
 		let draw_data = imgui_context.render();
+		render_draw_data(draw_data, framebuffer_scale(draw_data, self.is_window_highdpi), backend);
+	}
 
-		let fb_width = draw_data.display_size[0] * draw_data.framebuffer_scale[0];
-		let fb_height = draw_data.display_size[1] * draw_data.framebuffer_scale[1];
+	/// Add fonts with `builder` instead of by hand, so the atlas can be rebuilt automatically whenever the window's
+	/// dpi scale changes, for example when it moves to a highdpi monitor. `builder` is given a cleared atlas and the scale,
+	/// and should multiply its font sizes by the scale. The first build happens on the next update.
+	///
+	/// The style's sizes are scaled along with the fonts, starting from the sizes at the time of the first build,
+	/// so sizes changed after that are reset on the next rebuild. If a rebuild fails, the fonts go back to the last scale that worked.
+	///
+	/// A rebuild replaces every font, so [`imgui::FontId`]s kept from an earlier build go stale and pushing them panics.
+	/// Use [`Renderer::set_font_manager`] instead to look fonts up by handle. Setting a builder drops the font manager
+	pub fn set_font_builder<F: FnMut(&mut imgui::FontAtlas, f32) + 'static>(&mut self, builder: F) {
+		self.font_builder = Some(Box::new(builder));
+		self.font_manager = None;
+		self.font_scale = None;
+		self.failed_font_scale = None;
+	}
 
-		if !(fb_width > 0.0 && fb_height > 0.0) || draw_data.draw_lists_count() == 0 {
-			return;
+	/// Build the atlas from `fonts` instead of by hand, rebuilding it whenever the dpi scale changes like
+	/// [`Renderer::set_font_builder`] does, which this replaces. The first build happens on the next update.
+	///
+	/// Look the fonts up with [`Renderer::font_id`]
+	pub fn set_font_manager(&mut self, fonts: FontManager) {
+		self.font_manager = Some(fonts);
+		self.font_builder = None;
+		self.font_scale = None;
+		self.failed_font_scale = None;
+	}
+
+	/// The imgui font behind `handle` in the font manager, as of the last build.
+	/// None if there's no font manager or it hasn't been built yet. Look it up again after every update, since a dpi change
+	/// rebuilds the fonts and leaves the old id stale
+	pub fn font_id(&self, handle: FontHandle) -> Option<imgui::FontId> {
+		self.font_manager.as_ref()?.font_id(handle)
+	}
+
+	/// The dpi scale the fonts were last built for by the font manager or builder
+	pub fn font_scale(&self) -> Option<f32> {
+		self.font_scale
+	}
+
+	/// Like [`Renderer::set_font_manager`], but builds and uploads the atlas right away, at the dpi scale of the last build
+	/// if there was one. Later dpi changes rebuild from `fonts` too.
+	///
+	/// The atlas is replaced before the upload, so on error the fonts are gone: the old texture doesn't match the new atlas,
+	/// and [`imgui::FontId`]s from before the call point to freed fonts. Text is drawn garbled until a rebuild works
+	pub fn rebuild_fonts(&mut self, imgui_context: &mut imgui::Context, _raylib_handle: &mut RaylibHandle, _raylib_thread: &RaylibThread, fonts: FontManager) -> Result<(), Error> {
+		let scale = self.font_scale.unwrap_or(1.0);
+		self.font_manager = Some(fonts);
+		self.font_builder = None;
+		self.failed_font_scale = None;
+
+		self.build_fonts(imgui_context, scale)?;
+		self.font_scale = Some(scale);
+		Ok(())
+	}
+
+	/// Rebuild and upload the font atlas. Panics if the upload fails, see [`Renderer::try_reload_fonts`]
+	pub fn reload_fonts(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, raylib_thread: &RaylibThread) {
+		self.try_reload_fonts(imgui_context, raylib_handle, raylib_thread).unwrap_or_else(|err| panic!("{err}"));
+	}
+
+	/// Rebuild and upload the font atlas. On error the previous atlas texture is kept,
+	/// though it only matches the atlas if no fonts were added or cleared since it was uploaded.
+	/// The handle and thread aren't used, they just make sure the window is open on this thread
+	pub fn try_reload_fonts(&mut self, imgui_context: &mut imgui::Context, _raylib_handle: &mut RaylibHandle, _raylib_thread: &RaylibThread) -> Result<(), Error> {
+		self.font_texture = Some(Self::upload_font_atlas(imgui_context, self.font_texture_filter)?);
+		Ok(())
+	}
+
+	/// Build the atlas if needed and upload it as a new texture. Imgui's texture id only changes if the upload works.
+	/// This goes through ffi since there's no [`RaylibThread`] during update
+	fn upload_font_atlas(imgui_context: &mut imgui::Context, filter: TextureFilter) -> Result<Texture2D, Error> {
+		let image = Self::font_atlas_image(imgui_context)?;
+
+		let raw_texture = unsafe { ffi::LoadTextureFromImage(*image) };
+		if raw_texture.id == 0 {
+			return Err(Error::FontAtlasUpload { width: image.width as _, height: image.height as _ });
 		}
 
+		drop(image);
+
+		let font_texture = unsafe { Texture2D::from_raw(raw_texture) };
 		unsafe {
-			ffi::rlDrawRenderBatchActive();
-			ffi::rlDisableBackfaceCulling();
-		}
-
-		for draw_list in draw_data.draw_lists() {
-			for command in draw_list.commands() {
-				match command {
-					DrawCmd::Elements { count, cmd_params } => {
-						unsafe {
-							Self::enable_scissor(
-								cmd_params.clip_rect[0] - draw_data.display_pos[0],
-								cmd_params.clip_rect[1] - draw_data.display_pos[1],
-								cmd_params.clip_rect[2] - (cmd_params.clip_rect[0] - draw_data.display_pos[0]),
-								cmd_params.clip_rect[3] - (cmd_params.clip_rect[1] - draw_data.display_pos[1]),
-								display_framebuffer_scale,
-								display_size,
-							);
-
-							Self::render_triangles(count, cmd_params.idx_offset, cmd_params.vtx_offset, draw_list.idx_buffer(), draw_list.vtx_buffer(), cmd_params.texture_id);
-
-							ffi::rlDrawRenderBatchActive();
-						}
-					}
-					DrawCmd::ResetRenderState => {
-						// TODO: Figure out what to do here
-						unsafe {
-							ffi::rlSetTexture(0);
-						}
-					}
-					DrawCmd::RawCallback { callback, raw_cmd } => {
-						unsafe {
-							callback(draw_list.raw(), raw_cmd);
-						}
-					}
-				}
-			}
+			ffi::SetTextureFilter(raw_texture, filter as _);
+		}
+
+		imgui_context.fonts().tex_id = TextureId::from(font_texture.id as usize);
+
+		Ok(font_texture)
+	}
+
+	/// Build the atlas and copy it into a raylib image, checking that it has a size raylib can upload
+	fn font_atlas_image(imgui_context: &mut imgui::Context) -> Result<Image, Error> {
+		let atlas = imgui_context.fonts().build_rgba32_texture();
+		let (width, height) = (atlas.width, atlas.height);
+
+		if width == 0 || height == 0 || atlas.data.len() < width as usize * height as usize * 4 {
+			return Err(Error::InvalidFontAtlasSize { width, height });
 		}
 
+		if width > MAX_FONT_ATLAS_SIZE || height > MAX_FONT_ATLAS_SIZE {
+			return Err(Error::FontAtlasTooLarge { width, height, max: MAX_FONT_ATLAS_SIZE });
+		}
+
+		let image = Image::gen_image_color(width as _, height as _, Color::WHITE);
+
 		unsafe {
-			ffi::rlSetTexture(0);
-			ffi::rlDisableScissorTest();
-			ffi::rlEnableBackfaceCulling();
+			ptr::copy(atlas.data.as_ptr(), image.data() as _, width as usize * height as usize * 4);
+		}
+
+		Ok(image)
+	}
+}
+
+/// Imgui's framebuffer scale is the dpi scale, but raylib only scales the framebuffer up when the window is highdpi
+fn framebuffer_scale(draw_data: &imgui::DrawData, is_window_highdpi: bool) -> [f32; 2] {
+	if is_window_highdpi {
+		draw_data.framebuffer_scale
+	} else {
+		[1.0, 1.0]
+	}
+}
+
+/// Put back every size [`imgui::Style::scale_all_sizes`] changes, leaving colors and everything else alone
+fn reset_style_sizes(style: &mut imgui::Style, base: &imgui::Style) {
+	style.window_padding = base.window_padding;
+	style.window_rounding = base.window_rounding;
+	style.window_min_size = base.window_min_size;
+	style.child_rounding = base.child_rounding;
+	style.popup_rounding = base.popup_rounding;
+	style.frame_padding = base.frame_padding;
+	style.frame_rounding = base.frame_rounding;
+	style.item_spacing = base.item_spacing;
+	style.item_inner_spacing = base.item_inner_spacing;
+	style.cell_padding = base.cell_padding;
+	style.touch_extra_padding = base.touch_extra_padding;
+	style.indent_spacing = base.indent_spacing;
+	style.columns_min_spacing = base.columns_min_spacing;
+	style.scrollbar_size = base.scrollbar_size;
+	style.scrollbar_rounding = base.scrollbar_rounding;
+	style.grab_min_size = base.grab_min_size;
+	style.grab_rounding = base.grab_rounding;
+	style.log_slider_deadzone = base.log_slider_deadzone;
+	style.tab_rounding = base.tab_rounding;
+	style.tab_min_width_for_close_button = base.tab_min_width_for_close_button;
+	style.display_window_padding = base.display_window_padding;
+	style.display_safe_area_padding = base.display_safe_area_padding;
+	style.mouse_cursor_scale = base.mouse_cursor_scale;
+}
+
+#[cfg(test)]
+mod tests {
+	use raylib::prelude::*;
+	use imgui::Key;
+	use crate::backend::{RecordingBackend, RenderCommand};
+	use crate::test_support::{context, ScriptedInput, TestContext};
+	use crate::config::CursorPolicy;
+	use crate::fonts::FontManager;
+	use crate::touch::TouchConfig;
+	use crate::{Renderer, RendererConfig};
+
+	#[test]
+	fn headless_renderer_runs_a_frame() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+
+		let mut input = ScriptedInput { mouse_position: Vector2::new(40.0, 30.0), ..ScriptedInput::default() };
+		input.press_mouse_button(MouseButton::MOUSE_BUTTON_LEFT);
+
+		// New windows are hidden for their first frame, so run two
+		for frame in 0..2 {
+			if frame == 1 {
+				input.release_mouse_button(MouseButton::MOUSE_BUTTON_LEFT);
+			}
+
+			renderer.update_from(&mut ctx.imgui, &mut input);
+			input.end_frame();
+
+			let ui = ctx.imgui.new_frame();
+			ui.window("Test").position([0.0, 0.0], imgui::Condition::Always).build(|| ui.text("Hello"));
+
+			let io = ctx.imgui.io();
+			assert_eq!(io.display_size, [800.0, 600.0]);
+			assert_eq!(io.mouse_pos, [40.0, 30.0]);
+			assert_eq!(io.mouse_down[0], frame == 0);
+
+			if frame == 0 {
+				ctx.imgui.render();
+			}
 		}
+
+		let mut backend = RecordingBackend::new();
+		renderer.render_with(&mut ctx.imgui, &mut backend);
+
+		let Some(RenderCommand::BeginFrame(frame)) = backend.commands().first() else { panic!("no frame was drawn") };
+		assert_eq!(frame.display_size, [800.0, 600.0]);
+		assert!(backend.triangle_count() > 0);
+		assert_eq!(backend.commands().last(), Some(&RenderCommand::EndFrame));
 	}
 
-	unsafe fn enable_scissor(x: f32, y: f32, w: f32, h: f32, display_framebuffer_scale: [f32; 2], display_size: [f32; 2]) {
-		ffi::rlEnableScissorTest();
+	#[test]
+	fn headless_font_builder_follows_the_dpi_scale() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		renderer.set_font_builder(|fonts, scale| {
+			fonts.add_font(&[imgui::FontSource::DefaultFontData {
+				config: Some(imgui::FontConfig { size_pixels: 13.0 * scale, ..imgui::FontConfig::default() }),
+			}]);
+		});
+
+		let mut input = ScriptedInput { dpi_scale: Vector2::new(2.0, 2.0), ..ScriptedInput::default() };
+		let mut backend = RecordingBackend::new();
 
-		ffi::rlScissor(
-			(x * display_framebuffer_scale[0]) as _,
-			(display_size[1] - ((y + h).floor() * display_framebuffer_scale[1])) as _,
-			(w * display_framebuffer_scale[0]) as _,
-			(h * display_framebuffer_scale[1]) as _,
-		);
+		for is_highdpi in [false, true] {
+			input.is_highdpi = is_highdpi;
+			renderer.try_update_from(&mut ctx.imgui, &mut input).unwrap();
+			input.end_frame();
+
+			assert_eq!(ctx.imgui.fonts().fonts().len(), 1);
+
+			ctx.imgui.new_frame().get_background_draw_list().add_text([10.0, 10.0], [1.0, 1.0, 1.0], "Hello");
+			backend.clear();
+			renderer.render_with(&mut ctx.imgui, &mut backend);
+
+			let Some(RenderCommand::BeginFrame(frame)) = backend.commands().first() else { panic!("no frame was drawn") };
+			assert_eq!(frame.framebuffer_scale, if is_highdpi { [2.0, 2.0] } else { [1.0, 1.0] });
+		}
 	}
 
-	unsafe fn render_triangles(count: usize, indx_start: usize, vtx_start: usize, indx_buffer: &[DrawIdx], vert_buffer: &[DrawVert], texture_id: TextureId) {
-		if count < 3 { return; }
+	#[test]
+	fn font_manager_handles_survive_dpi_rebuilds() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+
+		let mut fonts = FontManager::new();
+		fonts.add_default(13.0);
+		let big = fonts.add_default(20.0);
+		renderer.set_font_manager(fonts);
+		assert_eq!(renderer.font_id(big), None);
+
+		let mut input = ScriptedInput::default();
+		for scale in [1.0, 2.0] {
+			input.dpi_scale = Vector2::new(scale, scale);
+			renderer.update_from(&mut ctx.imgui, &mut input);
+			input.end_frame();
+
+			let id = renderer.font_id(big).unwrap();
+			assert_eq!(ctx.imgui.fonts().get_font(id).unwrap().font_size, 20.0 * scale);
+
+			let ui = ctx.imgui.new_frame();
+			let font = ui.push_font(id);
+			ui.text("Hello");
+			font.pop();
+			ctx.imgui.render();
+		}
+	}
 
-		ffi::rlBegin(ffi::RL_TRIANGLES as _);
-		ffi::rlSetTexture(texture_id.id() as _);
+	/// Run one frame, handing the ui to `check`
+	fn run_frame(ctx: &mut TestContext, renderer: &mut Renderer, input: &mut ScriptedInput, check: impl FnOnce(&imgui::Ui)) {
+		renderer.update_from(&mut ctx.imgui, input);
+		input.end_frame();
 
-		for i in 0..count {
-			let indx = indx_buffer[indx_start + i] as usize;
-			Self::draw_vertex(vert_buffer[vtx_start + indx]);
+		check(ctx.imgui.new_frame());
+		ctx.imgui.render();
+	}
+
+	#[test]
+	fn held_key_is_released() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput::default();
+
+		input.press_key(KeyboardKey::KEY_A);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(ui.is_key_pressed(Key::A));
+			assert!(ui.is_key_down(Key::A));
+		});
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(!ui.is_key_pressed(Key::A));
+			assert!(ui.is_key_down(Key::A));
+		});
+
+		input.release_key(KeyboardKey::KEY_A);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(ui.is_key_released(Key::A));
+			assert!(!ui.is_key_down(Key::A));
+		});
+	}
+
+	#[test]
+	fn key_pressed_and_released_in_one_frame() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput::default();
+
+		// Raylib queues the press, but the key is already up by the time it's polled
+		input.key_queue.push_back(KeyboardKey::KEY_A as u32);
+
+		// Imgui spreads the press and release over two frames, so the press isn't lost
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(ui.is_key_pressed(Key::A)));
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.is_key_down(Key::A)));
+	}
+
+	#[test]
+	fn unmapped_key_keeps_the_rest_of_the_queue() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput::default();
+
+		// GLFW_KEY_WORLD_1, the iso <> key, which raylib has no KeyboardKey for
+		input.key_queue.push_back(161);
+		input.press_key(KeyboardKey::KEY_B);
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(ui.is_key_pressed(Key::B));
+			assert!(ui.is_key_down(Key::B));
+		});
+	}
+
+	#[test]
+	fn losing_focus_releases_held_input() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput::default();
+
+		input.press_key(KeyboardKey::KEY_LEFT_CONTROL);
+		input.press_key(KeyboardKey::KEY_A);
+		input.press_mouse_button(MouseButton::MOUSE_BUTTON_LEFT);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			// This imgui version asserts when asked about Key::ModCtrl, so check the modifier flag it sets instead
+			assert!(ui.io().key_ctrl);
+			assert!(ui.is_key_down(Key::A));
+			assert!(ui.io().mouse_down[0]);
+		});
+
+		// Everything is still held when the window loses focus, so the releases have to come from the renderer
+		input.is_focused = false;
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(!ui.io().key_ctrl);
+			assert!(!ui.is_key_down(Key::A));
+			assert!(!ui.io().mouse_down[0]);
+		});
+	}
+
+	#[test]
+	fn unfocused_window_only_ignores_the_keyboard() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput { is_focused: false, ..ScriptedInput::default() };
+
+		// Losing focus releases the mouse buttons, which imgui handles before moving the mouse
+		run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+
+		input.mouse_position = Vector2::new(10.0, 20.0);
+		input.press_key(KeyboardKey::KEY_A);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(!ui.is_key_down(Key::A));
+			assert_eq!(ui.io().mouse_pos, [10.0, 20.0]);
+		});
+	}
+
+	#[test]
+	fn no_cursor_change_gives_up_the_cursor() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput::default();
+
+		ctx.imgui.io_mut().config_flags.insert(imgui::ConfigFlags::NO_MOUSE_CURSOR_CHANGE);
+		run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+		assert!(!renderer.owns_cursor);
+
+		ctx.imgui.io_mut().config_flags.remove(imgui::ConfigFlags::NO_MOUSE_CURSOR_CHANGE);
+		run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+		assert!(renderer.owns_cursor);
+	}
+
+	#[test]
+	fn remapped_gamepad_button_releases_its_old_key() {
+		let mut ctx = context();
+		ctx.imgui.io_mut().config_flags.insert(imgui::ConfigFlags::NAV_ENABLE_GAMEPAD);
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput {
+			connected_gamepads: vec![0],
+			gamepad_buttons_down: vec![(0, GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN)],
+			..ScriptedInput::default()
+		};
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(ui.is_key_down(Key::GamepadFaceDown)));
+
+		let mut config = renderer.gamepad_config().clone();
+		config.remap(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN, Key::GamepadFaceRight);
+		renderer.set_gamepad_config(config);
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(!ui.is_key_down(Key::GamepadFaceDown));
+			assert!(ui.is_key_down(Key::GamepadFaceRight));
+		});
+
+		let mut config = renderer.gamepad_config().clone();
+		config.unmap(GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN);
+		renderer.set_gamepad_config(config);
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.is_key_down(Key::GamepadFaceRight)));
+	}
+
+	#[test]
+	fn gamepad_stick_dead_zone() {
+		let mut ctx = context();
+		ctx.imgui.io_mut().config_flags.insert(imgui::ConfigFlags::NAV_ENABLE_GAMEPAD);
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput {
+			connected_gamepads: vec![0],
+			gamepad_axes: vec![(0, GamepadAxis::GAMEPAD_AXIS_LEFT_X, 0.1)],
+			..ScriptedInput::default()
+		};
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.is_key_down(Key::GamepadLStickRight)));
+
+		input.gamepad_axes = vec![(0, GamepadAxis::GAMEPAD_AXIS_LEFT_X, 0.5)];
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(ui.is_key_down(Key::GamepadLStickRight));
+			assert!(!ui.is_key_down(Key::GamepadLStickLeft));
+		});
+	}
+
+	#[test]
+	fn backend_flags_replace_the_existing_ones() {
+		let mut ctx = context();
+		ctx.imgui.io_mut().backend_flags = imgui::BackendFlags::HAS_MOUSE_CURSORS;
+
+		Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard().backend_flags(imgui::BackendFlags::HAS_GAMEPAD));
+		assert_eq!(ctx.imgui.io().backend_flags, imgui::BackendFlags::HAS_GAMEPAD);
+	}
+
+	#[test]
+	fn drained_key_codes_are_kept() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput::default();
+
+		input.press_key(KeyboardKey::KEY_A);
+		input.key_queue.push_back(161);
+		run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+		assert_eq!(renderer.pressed_keys(), &[KeyboardKey::KEY_A as u32, 161]);
+
+		let mut gate = renderer.input_gate(&ctx.imgui, &mut input);
+		assert_eq!(gate.get_key_pressed(), Some(KeyboardKey::KEY_A as u32));
+		assert_eq!(gate.get_key_pressed(), Some(161));
+		assert_eq!(gate.get_key_pressed(), None);
+
+		// Imgui never sees the unmapped key, so it gets through anyway
+		let mut gate = renderer.input_gate(&ctx.imgui, &mut input).override_keyboard(true);
+		assert_eq!(gate.get_key_pressed(), Some(161));
+		assert_eq!(gate.get_key_pressed(), None);
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+		assert!(renderer.pressed_keys().is_empty());
+	}
+
+	#[test]
+	fn long_press_only_right_clicks() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard().touch(TouchConfig::touchscreen()));
+		// Raylib moves the mouse to the first touch point, so the scripted mouse follows it too
+		let mut input = ScriptedInput { mouse_position: Vector2::new(10.0, 20.0), touch_points: vec![Vector2::new(10.0, 20.0)], gesture: Gesture::GESTURE_TAP, ..ScriptedInput::default() };
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert_eq!(ui.io().mouse_pos, [10.0, 20.0]);
+			assert!(!ui.io().mouse_down[0]);
+		});
+
+		input.gesture = Gesture::GESTURE_HOLD;
+		input.gesture_hold_duration = 0.6;
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(!ui.io().mouse_down[0]);
+			assert!(ui.io().mouse_down[1]);
+		});
+
+		input.touch_points.clear();
+		input.gesture = Gesture::GESTURE_NONE;
+		for _ in 0..2 {
+			run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+				assert!(!ui.io().mouse_down[0]);
+				assert!(!ui.io().mouse_down[1]);
+			});
 		}
+	}
 
-		ffi::rlEnd();
+	#[test]
+	fn tap_clicks() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard().touch(TouchConfig::touchscreen()));
+		let mut input = ScriptedInput { mouse_position: Vector2::new(10.0, 20.0), touch_points: vec![Vector2::new(10.0, 20.0)], gesture: Gesture::GESTURE_TAP, ..ScriptedInput::default() };
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.io().mouse_down[0]));
+
+		// The press and release are sent together once the touch ends, and imgui spreads them over two frames
+		input.touch_points.clear();
+		input.gesture = Gesture::GESTURE_NONE;
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!(ui.io().mouse_down[0]);
+			assert_eq!(ui.io().mouse_pos, [10.0, 20.0]);
+		});
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.io().mouse_down[0]));
 	}
 
-	unsafe fn draw_vertex(vert: DrawVert) {
-		ffi::rlColor4ub(vert.col[0], vert.col[1], vert.col[2], vert.col[3]);
-		ffi::rlTexCoord2f(vert.uv[0], vert.uv[1]);
-		ffi::rlVertex2f(vert.pos[0], vert.pos[1]);
+	#[test]
+	fn touch_drag_scrolls_without_clicking() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard().touch(TouchConfig::touchscreen()));
+		let mut input = ScriptedInput { mouse_position: Vector2::new(10.0, 20.0), touch_points: vec![Vector2::new(10.0, 20.0)], gesture: Gesture::GESTURE_DRAG, ..ScriptedInput::default() };
+
+		run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+
+		input.gesture_drag = Vector2::new(0.0, 0.1);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+			assert!((ui.io().mouse_wheel - 2.0).abs() < 1e-4);
+			assert!(!ui.io().mouse_down[0]);
+		});
+
+		input.touch_points.clear();
+		input.gesture = Gesture::GESTURE_NONE;
+		for _ in 0..2 {
+			run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.io().mouse_down[0]));
+		}
 	}
 
-	pub fn reload_fonts(&mut self, imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, raylib_thread: &RaylibThread) {
-		self.font_texture = Self::reload_fonts_impl(imgui_context, raylib_handle, raylib_thread);
+	#[test]
+	fn mouse_drag_gesture_doesnt_scroll() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard().touch(TouchConfig::touchscreen()));
+		let mut input = ScriptedInput { gesture: Gesture::GESTURE_DRAG, ..ScriptedInput::default() };
+		input.press_mouse_button(MouseButton::MOUSE_BUTTON_LEFT);
+
+		// Raylib turns mouse drags into gestures too, but without any touch points
+		for y in [0.0, 0.1] {
+			input.gesture_drag = Vector2::new(0.0, y);
+			run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert_eq!(ui.io().mouse_wheel, 0.0));
+		}
 	}
 
-	fn reload_fonts_impl(imgui_context: &mut imgui::Context, raylib_handle: &mut RaylibHandle, raylib_thread: &RaylibThread) -> Texture2D {
-		let atlas = imgui_context.fonts().build_rgba32_texture();
-		let image = Image::gen_image_color(atlas.width as _, atlas.height as _, Color::WHITE);
-		
-		unsafe {
-			ptr::copy(atlas.data.as_ptr(), image.data() as _, atlas.width as usize * atlas.height as usize * 4);
+	#[test]
+	fn locked_cursor_mutes_the_mouse() {
+		let mut ctx = context();
+		let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard());
+		let mut input = ScriptedInput { mouse_position: Vector2::new(10.0, 20.0), ..ScriptedInput::default() };
+
+		input.press_mouse_button(MouseButton::MOUSE_BUTTON_LEFT);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(ui.io().mouse_down[0]));
+
+		renderer.set_cursor_locked(true);
+		input.mouse_wheel = Vector2::new(0.0, 1.0);
+		for _ in 0..2 {
+			run_frame(&mut ctx, &mut renderer, &mut input, |ui| {
+				assert!(!ui.io().mouse_down[0]);
+				assert_eq!(ui.io().mouse_wheel, 0.0);
+			});
+		}
 
-			let font_texture = raylib_handle.load_texture_from_image(raylib_thread, &image).unwrap(); // TODO: Don't unwrap
-			drop(image);
+		run_frame(&mut ctx, &mut renderer, &mut input, |ui| assert!(!ui.is_mouse_pos_valid(ui.io().mouse_pos)));
+	}
 
-			imgui_context.fonts().tex_id = TextureId::from(font_texture.id as usize);
+	#[test]
+	fn game_cursor_policy_doesnt_move_the_cursor() {
+		for (policy, warps) in [(CursorPolicy::ImGui, 1), (CursorPolicy::Game, 0)] {
+			let mut ctx = context();
+			let mut renderer = Renderer::headless(&mut ctx.imgui, RendererConfig::new().no_clipboard().cursor_policy(policy));
+			let mut input = ScriptedInput::default();
 
-			font_texture
+			ctx.imgui.io_mut().want_set_mouse_pos = true;
+			run_frame(&mut ctx, &mut renderer, &mut input, |_| {});
+			assert_eq!(input.warps.len(), warps);
 		}
 	}
 }
\ No newline at end of file
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-use std::sync::LazyLock;
 use raylib::prelude::*;
 pub static MOUSE_CURSOR_MAP : [MouseCursor; 9]  = [
 	MouseCursor::MOUSE_CURSOR_ARROW,
@@ -16,114 +14,137 @@ pub static MOUSE_CURSOR_MAP : [MouseCursor; 9]  = [
 	MouseCursor::MOUSE_CURSOR_NOT_ALLOWED,
 ];
 
-pub static KEYBOARD_MAP : LazyLock<HashMap<KeyboardKey, imgui::Key>> = LazyLock::new(|| {
-	let mut map = HashMap::with_capacity(imgui::Key::COUNT);
+pub static MOUSE_BUTTON_MAP : [(MouseButton, imgui::MouseButton); 5] = [
+	(MouseButton::MOUSE_BUTTON_LEFT, imgui::MouseButton::Left),
+	(MouseButton::MOUSE_BUTTON_RIGHT, imgui::MouseButton::Right),
+	(MouseButton::MOUSE_BUTTON_MIDDLE, imgui::MouseButton::Middle),
+	(MouseButton::MOUSE_BUTTON_FORWARD, imgui::MouseButton::Extra1),
+	(MouseButton::MOUSE_BUTTON_BACK, imgui::MouseButton::Extra2),
+];
+
+/// One past the highest raylib key code
+pub const KEYBOARD_MAP_SIZE: usize = KeyboardKey::KEY_KB_MENU as usize + 1;
+
+/// Raylib key codes to imgui keys, indexed by key code
+pub static KEYBOARD_MAP : [Option<imgui::Key>; KEYBOARD_MAP_SIZE] = {
+	let mut map = [None; KEYBOARD_MAP_SIZE];
 
-	map.insert(KeyboardKey::KEY_APOSTROPHE, imgui::Key::Apostrophe);
-	map.insert(KeyboardKey::KEY_COMMA, imgui::Key::Comma);
-	map.insert(KeyboardKey::KEY_MINUS, imgui::Key::Minus);
-	map.insert(KeyboardKey::KEY_PERIOD, imgui::Key::Period);
-	map.insert(KeyboardKey::KEY_SLASH, imgui::Key::Slash);
-	map.insert(KeyboardKey::KEY_ZERO, imgui::Key::Alpha0);
-	map.insert(KeyboardKey::KEY_ONE, imgui::Key::Alpha1);
-	map.insert(KeyboardKey::KEY_TWO, imgui::Key::Alpha2);
-	map.insert(KeyboardKey::KEY_THREE, imgui::Key::Alpha3);
-	map.insert(KeyboardKey::KEY_FOUR, imgui::Key::Alpha4);
-	map.insert(KeyboardKey::KEY_FIVE, imgui::Key::Alpha5);
-	map.insert(KeyboardKey::KEY_SIX, imgui::Key::Alpha6);
-	map.insert(KeyboardKey::KEY_SEVEN, imgui::Key::Alpha7);
-	map.insert(KeyboardKey::KEY_EIGHT, imgui::Key::Alpha8);
-	map.insert(KeyboardKey::KEY_NINE, imgui::Key::Alpha9);
-	map.insert(KeyboardKey::KEY_SEMICOLON, imgui::Key::Semicolon);
-	map.insert(KeyboardKey::KEY_EQUAL, imgui::Key::Equal);
-	map.insert(KeyboardKey::KEY_A, imgui::Key::A);
-	map.insert(KeyboardKey::KEY_B, imgui::Key::B);
-	map.insert(KeyboardKey::KEY_C, imgui::Key::C);
-	map.insert(KeyboardKey::KEY_D, imgui::Key::D);
-	map.insert(KeyboardKey::KEY_E, imgui::Key::E);
-	map.insert(KeyboardKey::KEY_F, imgui::Key::F);
-	map.insert(KeyboardKey::KEY_G, imgui::Key::G);
-	map.insert(KeyboardKey::KEY_H, imgui::Key::H);
-	map.insert(KeyboardKey::KEY_I, imgui::Key::I);
-	map.insert(KeyboardKey::KEY_J, imgui::Key::J);
-	map.insert(KeyboardKey::KEY_K, imgui::Key::K);
-	map.insert(KeyboardKey::KEY_L, imgui::Key::L);
-	map.insert(KeyboardKey::KEY_M, imgui::Key::M);
-	map.insert(KeyboardKey::KEY_N, imgui::Key::N);
-	map.insert(KeyboardKey::KEY_O, imgui::Key::O);
-	map.insert(KeyboardKey::KEY_P, imgui::Key::P);
-	map.insert(KeyboardKey::KEY_Q, imgui::Key::Q);
-	map.insert(KeyboardKey::KEY_R, imgui::Key::R);
-	map.insert(KeyboardKey::KEY_S, imgui::Key::S);
-	map.insert(KeyboardKey::KEY_T, imgui::Key::T);
-	map.insert(KeyboardKey::KEY_U, imgui::Key::U);
-	map.insert(KeyboardKey::KEY_V, imgui::Key::V);
-	map.insert(KeyboardKey::KEY_W, imgui::Key::W);
-	map.insert(KeyboardKey::KEY_X, imgui::Key::X);
-	map.insert(KeyboardKey::KEY_Y, imgui::Key::Y);
-	map.insert(KeyboardKey::KEY_Z, imgui::Key::Z);
-	map.insert(KeyboardKey::KEY_SPACE, imgui::Key::Space);
-	map.insert(KeyboardKey::KEY_ESCAPE, imgui::Key::Escape);
-	map.insert(KeyboardKey::KEY_ENTER, imgui::Key::Enter);
-	map.insert(KeyboardKey::KEY_TAB, imgui::Key::Tab);
-	map.insert(KeyboardKey::KEY_BACKSPACE, imgui::Key::Backspace);
-	map.insert(KeyboardKey::KEY_INSERT, imgui::Key::Insert);
-	map.insert(KeyboardKey::KEY_DELETE, imgui::Key::Delete);
-	map.insert(KeyboardKey::KEY_RIGHT, imgui::Key::RightArrow);
-	map.insert(KeyboardKey::KEY_LEFT, imgui::Key::LeftArrow);
-	map.insert(KeyboardKey::KEY_DOWN, imgui::Key::DownArrow);
-	map.insert(KeyboardKey::KEY_UP, imgui::Key::UpArrow);
-	map.insert(KeyboardKey::KEY_PAGE_UP, imgui::Key::PageUp);
-	map.insert(KeyboardKey::KEY_PAGE_DOWN, imgui::Key::PageDown);
-	map.insert(KeyboardKey::KEY_HOME, imgui::Key::Home);
-	map.insert(KeyboardKey::KEY_END, imgui::Key::End);
-	map.insert(KeyboardKey::KEY_CAPS_LOCK, imgui::Key::CapsLock);
-	map.insert(KeyboardKey::KEY_SCROLL_LOCK, imgui::Key::ScrollLock);
-	map.insert(KeyboardKey::KEY_NUM_LOCK, imgui::Key::NumLock);
-	map.insert(KeyboardKey::KEY_PRINT_SCREEN, imgui::Key::PrintScreen);
-	map.insert(KeyboardKey::KEY_PAUSE, imgui::Key::Pause);
-	map.insert(KeyboardKey::KEY_F1, imgui::Key::F1);
-	map.insert(KeyboardKey::KEY_F2, imgui::Key::F2);
-	map.insert(KeyboardKey::KEY_F3, imgui::Key::F3);
-	map.insert(KeyboardKey::KEY_F4, imgui::Key::F4);
-	map.insert(KeyboardKey::KEY_F5, imgui::Key::F5);
-	map.insert(KeyboardKey::KEY_F6, imgui::Key::F6);
-	map.insert(KeyboardKey::KEY_F7, imgui::Key::F7);
-	map.insert(KeyboardKey::KEY_F8, imgui::Key::F8);
-	map.insert(KeyboardKey::KEY_F9, imgui::Key::F9);
-	map.insert(KeyboardKey::KEY_F10, imgui::Key::F10);
-	map.insert(KeyboardKey::KEY_F11, imgui::Key::F11);
-	map.insert(KeyboardKey::KEY_F12, imgui::Key::F12);
-	map.insert(KeyboardKey::KEY_LEFT_SHIFT, imgui::Key::LeftShift);
-	map.insert(KeyboardKey::KEY_LEFT_CONTROL, imgui::Key::LeftCtrl);
-	map.insert(KeyboardKey::KEY_LEFT_ALT, imgui::Key::LeftAlt);
-	map.insert(KeyboardKey::KEY_LEFT_SUPER, imgui::Key::LeftSuper);
-	map.insert(KeyboardKey::KEY_RIGHT_SHIFT, imgui::Key::RightShift);
-	map.insert(KeyboardKey::KEY_RIGHT_CONTROL, imgui::Key::RightCtrl);
-	map.insert(KeyboardKey::KEY_RIGHT_ALT, imgui::Key::RightAlt);
-	map.insert(KeyboardKey::KEY_RIGHT_SUPER, imgui::Key::RightSuper);
-	map.insert(KeyboardKey::KEY_KB_MENU, imgui::Key::Menu);
-	map.insert(KeyboardKey::KEY_LEFT_BRACKET, imgui::Key::LeftBracket);
-	map.insert(KeyboardKey::KEY_BACKSLASH, imgui::Key::Backslash);
-	map.insert(KeyboardKey::KEY_RIGHT_BRACKET, imgui::Key::RightBracket);
-	map.insert(KeyboardKey::KEY_GRAVE, imgui::Key::GraveAccent);
-	map.insert(KeyboardKey::KEY_KP_0, imgui::Key::Keypad0);
-	map.insert(KeyboardKey::KEY_KP_1, imgui::Key::Keypad1);
-	map.insert(KeyboardKey::KEY_KP_2, imgui::Key::Keypad2);
-	map.insert(KeyboardKey::KEY_KP_3, imgui::Key::Keypad3);
-	map.insert(KeyboardKey::KEY_KP_4, imgui::Key::Keypad4);
-	map.insert(KeyboardKey::KEY_KP_5, imgui::Key::Keypad5);
-	map.insert(KeyboardKey::KEY_KP_6, imgui::Key::Keypad6);
-	map.insert(KeyboardKey::KEY_KP_7, imgui::Key::Keypad7);
-	map.insert(KeyboardKey::KEY_KP_8, imgui::Key::Keypad8);
-	map.insert(KeyboardKey::KEY_KP_9, imgui::Key::Keypad9);
-	map.insert(KeyboardKey::KEY_KP_DECIMAL, imgui::Key::KeypadDecimal);
-	map.insert(KeyboardKey::KEY_KP_DIVIDE, imgui::Key::KeypadDivide);
-	map.insert(KeyboardKey::KEY_KP_MULTIPLY, imgui::Key::KeypadMultiply);
-	map.insert(KeyboardKey::KEY_KP_SUBTRACT, imgui::Key::KeypadSubtract);
-	map.insert(KeyboardKey::KEY_KP_ADD, imgui::Key::KeypadAdd);
-	map.insert(KeyboardKey::KEY_KP_ENTER, imgui::Key::KeypadEnter);
-	map.insert(KeyboardKey::KEY_KP_EQUAL, imgui::Key::KeypadEqual);
+	map[KeyboardKey::KEY_APOSTROPHE as usize] = Some(imgui::Key::Apostrophe);
+	map[KeyboardKey::KEY_COMMA as usize] = Some(imgui::Key::Comma);
+	map[KeyboardKey::KEY_MINUS as usize] = Some(imgui::Key::Minus);
+	map[KeyboardKey::KEY_PERIOD as usize] = Some(imgui::Key::Period);
+	map[KeyboardKey::KEY_SLASH as usize] = Some(imgui::Key::Slash);
+	map[KeyboardKey::KEY_ZERO as usize] = Some(imgui::Key::Alpha0);
+	map[KeyboardKey::KEY_ONE as usize] = Some(imgui::Key::Alpha1);
+	map[KeyboardKey::KEY_TWO as usize] = Some(imgui::Key::Alpha2);
+	map[KeyboardKey::KEY_THREE as usize] = Some(imgui::Key::Alpha3);
+	map[KeyboardKey::KEY_FOUR as usize] = Some(imgui::Key::Alpha4);
+	map[KeyboardKey::KEY_FIVE as usize] = Some(imgui::Key::Alpha5);
+	map[KeyboardKey::KEY_SIX as usize] = Some(imgui::Key::Alpha6);
+	map[KeyboardKey::KEY_SEVEN as usize] = Some(imgui::Key::Alpha7);
+	map[KeyboardKey::KEY_EIGHT as usize] = Some(imgui::Key::Alpha8);
+	map[KeyboardKey::KEY_NINE as usize] = Some(imgui::Key::Alpha9);
+	map[KeyboardKey::KEY_SEMICOLON as usize] = Some(imgui::Key::Semicolon);
+	map[KeyboardKey::KEY_EQUAL as usize] = Some(imgui::Key::Equal);
+	map[KeyboardKey::KEY_A as usize] = Some(imgui::Key::A);
+	map[KeyboardKey::KEY_B as usize] = Some(imgui::Key::B);
+	map[KeyboardKey::KEY_C as usize] = Some(imgui::Key::C);
+	map[KeyboardKey::KEY_D as usize] = Some(imgui::Key::D);
+	map[KeyboardKey::KEY_E as usize] = Some(imgui::Key::E);
+	map[KeyboardKey::KEY_F as usize] = Some(imgui::Key::F);
+	map[KeyboardKey::KEY_G as usize] = Some(imgui::Key::G);
+	map[KeyboardKey::KEY_H as usize] = Some(imgui::Key::H);
+	map[KeyboardKey::KEY_I as usize] = Some(imgui::Key::I);
+	map[KeyboardKey::KEY_J as usize] = Some(imgui::Key::J);
+	map[KeyboardKey::KEY_K as usize] = Some(imgui::Key::K);
+	map[KeyboardKey::KEY_L as usize] = Some(imgui::Key::L);
+	map[KeyboardKey::KEY_M as usize] = Some(imgui::Key::M);
+	map[KeyboardKey::KEY_N as usize] = Some(imgui::Key::N);
+	map[KeyboardKey::KEY_O as usize] = Some(imgui::Key::O);
+	map[KeyboardKey::KEY_P as usize] = Some(imgui::Key::P);
+	map[KeyboardKey::KEY_Q as usize] = Some(imgui::Key::Q);
+	map[KeyboardKey::KEY_R as usize] = Some(imgui::Key::R);
+	map[KeyboardKey::KEY_S as usize] = Some(imgui::Key::S);
+	map[KeyboardKey::KEY_T as usize] = Some(imgui::Key::T);
+	map[KeyboardKey::KEY_U as usize] = Some(imgui::Key::U);
+	map[KeyboardKey::KEY_V as usize] = Some(imgui::Key::V);
+	map[KeyboardKey::KEY_W as usize] = Some(imgui::Key::W);
+	map[KeyboardKey::KEY_X as usize] = Some(imgui::Key::X);
+	map[KeyboardKey::KEY_Y as usize] = Some(imgui::Key::Y);
+	map[KeyboardKey::KEY_Z as usize] = Some(imgui::Key::Z);
+	map[KeyboardKey::KEY_SPACE as usize] = Some(imgui::Key::Space);
+	map[KeyboardKey::KEY_ESCAPE as usize] = Some(imgui::Key::Escape);
+	map[KeyboardKey::KEY_ENTER as usize] = Some(imgui::Key::Enter);
+	map[KeyboardKey::KEY_TAB as usize] = Some(imgui::Key::Tab);
+	map[KeyboardKey::KEY_BACKSPACE as usize] = Some(imgui::Key::Backspace);
+	map[KeyboardKey::KEY_INSERT as usize] = Some(imgui::Key::Insert);
+	map[KeyboardKey::KEY_DELETE as usize] = Some(imgui::Key::Delete);
+	map[KeyboardKey::KEY_RIGHT as usize] = Some(imgui::Key::RightArrow);
+	map[KeyboardKey::KEY_LEFT as usize] = Some(imgui::Key::LeftArrow);
+	map[KeyboardKey::KEY_DOWN as usize] = Some(imgui::Key::DownArrow);
+	map[KeyboardKey::KEY_UP as usize] = Some(imgui::Key::UpArrow);
+	map[KeyboardKey::KEY_PAGE_UP as usize] = Some(imgui::Key::PageUp);
+	map[KeyboardKey::KEY_PAGE_DOWN as usize] = Some(imgui::Key::PageDown);
+	map[KeyboardKey::KEY_HOME as usize] = Some(imgui::Key::Home);
+	map[KeyboardKey::KEY_END as usize] = Some(imgui::Key::End);
+	map[KeyboardKey::KEY_CAPS_LOCK as usize] = Some(imgui::Key::CapsLock);
+	map[KeyboardKey::KEY_SCROLL_LOCK as usize] = Some(imgui::Key::ScrollLock);
+	map[KeyboardKey::KEY_NUM_LOCK as usize] = Some(imgui::Key::NumLock);
+	map[KeyboardKey::KEY_PRINT_SCREEN as usize] = Some(imgui::Key::PrintScreen);
+	map[KeyboardKey::KEY_PAUSE as usize] = Some(imgui::Key::Pause);
+	map[KeyboardKey::KEY_F1 as usize] = Some(imgui::Key::F1);
+	map[KeyboardKey::KEY_F2 as usize] = Some(imgui::Key::F2);
+	map[KeyboardKey::KEY_F3 as usize] = Some(imgui::Key::F3);
+	map[KeyboardKey::KEY_F4 as usize] = Some(imgui::Key::F4);
+	map[KeyboardKey::KEY_F5 as usize] = Some(imgui::Key::F5);
+	map[KeyboardKey::KEY_F6 as usize] = Some(imgui::Key::F6);
+	map[KeyboardKey::KEY_F7 as usize] = Some(imgui::Key::F7);
+	map[KeyboardKey::KEY_F8 as usize] = Some(imgui::Key::F8);
+	map[KeyboardKey::KEY_F9 as usize] = Some(imgui::Key::F9);
+	map[KeyboardKey::KEY_F10 as usize] = Some(imgui::Key::F10);
+	map[KeyboardKey::KEY_F11 as usize] = Some(imgui::Key::F11);
+	map[KeyboardKey::KEY_F12 as usize] = Some(imgui::Key::F12);
+	map[KeyboardKey::KEY_LEFT_SHIFT as usize] = Some(imgui::Key::LeftShift);
+	map[KeyboardKey::KEY_LEFT_CONTROL as usize] = Some(imgui::Key::LeftCtrl);
+	map[KeyboardKey::KEY_LEFT_ALT as usize] = Some(imgui::Key::LeftAlt);
+	map[KeyboardKey::KEY_LEFT_SUPER as usize] = Some(imgui::Key::LeftSuper);
+	map[KeyboardKey::KEY_RIGHT_SHIFT as usize] = Some(imgui::Key::RightShift);
+	map[KeyboardKey::KEY_RIGHT_CONTROL as usize] = Some(imgui::Key::RightCtrl);
+	map[KeyboardKey::KEY_RIGHT_ALT as usize] = Some(imgui::Key::RightAlt);
+	map[KeyboardKey::KEY_RIGHT_SUPER as usize] = Some(imgui::Key::RightSuper);
+	map[KeyboardKey::KEY_KB_MENU as usize] = Some(imgui::Key::Menu);
+	map[KeyboardKey::KEY_LEFT_BRACKET as usize] = Some(imgui::Key::LeftBracket);
+	map[KeyboardKey::KEY_BACKSLASH as usize] = Some(imgui::Key::Backslash);
+	map[KeyboardKey::KEY_RIGHT_BRACKET as usize] = Some(imgui::Key::RightBracket);
+	map[KeyboardKey::KEY_GRAVE as usize] = Some(imgui::Key::GraveAccent);
+	map[KeyboardKey::KEY_KP_0 as usize] = Some(imgui::Key::Keypad0);
+	map[KeyboardKey::KEY_KP_1 as usize] = Some(imgui::Key::Keypad1);
+	map[KeyboardKey::KEY_KP_2 as usize] = Some(imgui::Key::Keypad2);
+	map[KeyboardKey::KEY_KP_3 as usize] = Some(imgui::Key::Keypad3);
+	map[KeyboardKey::KEY_KP_4 as usize] = Some(imgui::Key::Keypad4);
+	map[KeyboardKey::KEY_KP_5 as usize] = Some(imgui::Key::Keypad5);
+	map[KeyboardKey::KEY_KP_6 as usize] = Some(imgui::Key::Keypad6);
+	map[KeyboardKey::KEY_KP_7 as usize] = Some(imgui::Key::Keypad7);
+	map[KeyboardKey::KEY_KP_8 as usize] = Some(imgui::Key::Keypad8);
+	map[KeyboardKey::KEY_KP_9 as usize] = Some(imgui::Key::Keypad9);
+	map[KeyboardKey::KEY_KP_DECIMAL as usize] = Some(imgui::Key::KeypadDecimal);
+	map[KeyboardKey::KEY_KP_DIVIDE as usize] = Some(imgui::Key::KeypadDivide);
+	map[KeyboardKey::KEY_KP_MULTIPLY as usize] = Some(imgui::Key::KeypadMultiply);
+	map[KeyboardKey::KEY_KP_SUBTRACT as usize] = Some(imgui::Key::KeypadSubtract);
+	map[KeyboardKey::KEY_KP_ADD as usize] = Some(imgui::Key::KeypadAdd);
+	map[KeyboardKey::KEY_KP_ENTER as usize] = Some(imgui::Key::KeypadEnter);
+	map[KeyboardKey::KEY_KP_EQUAL as usize] = Some(imgui::Key::KeypadEqual);
 
 	map
-});
\ No newline at end of file
+};
+
+/// The imgui key for a raylib key, if imgui has one
+pub fn imgui_key(key: KeyboardKey) -> Option<imgui::Key> {
+	KEYBOARD_MAP.get(key as usize).copied().flatten()
+}
+
+/// The raylib and imgui keys for a raw raylib key code, if imgui has one
+pub fn keyboard_key(code: u32) -> Option<(KeyboardKey, imgui::Key)> {
+	let key = KEYBOARD_MAP.get(code as usize).copied().flatten()?;
+	Some((key_from_i32(code as i32)?, key))
+}
\ No newline at end of file
@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use raylib::prelude::*;
+use crate::input::InputSource;
+
+/// Imgui only allows one context at a time, so tests that need one take turns
+static CONTEXT_LOCK: Mutex<()> = Mutex::new(());
+
+pub struct TestContext {
+	pub imgui: imgui::Context,
+	// Declared after the context so it's released after the context is destroyed
+	_lock: MutexGuard<'static, ()>,
+}
+
+pub fn context() -> TestContext {
+	let lock = CONTEXT_LOCK.lock().unwrap_or_else(|err| err.into_inner());
+
+	let mut imgui = imgui::Context::create();
+	imgui.set_ini_filename(None);
+
+	TestContext { imgui, _lock: lock }
+}
+
+/// Input that comes from whatever a test puts in it instead of a window.
+/// The per-frame parts (the key and char queues, presses, releases and wheel) are used up by an update
+pub struct ScriptedInput {
+	pub screen_size: Vector2,
+	pub dpi_scale: Vector2,
+	pub is_highdpi: bool,
+	pub is_focused: bool,
+	pub keys_down: Vec<KeyboardKey>,
+	pub key_queue: VecDeque<u32>,
+	pub char_queue: VecDeque<char>,
+	pub mouse_position: Vector2,
+	pub mouse_wheel: Vector2,
+	pub mouse_down: Vec<MouseButton>,
+	pub mouse_pressed: Vec<MouseButton>,
+	pub mouse_released: Vec<MouseButton>,
+	pub connected_gamepads: Vec<i32>,
+	pub gamepad_buttons_down: Vec<(i32, GamepadButton)>,
+	pub gamepad_axes: Vec<(i32, GamepadAxis, f32)>,
+	pub touch_points: Vec<Vector2>,
+	pub gesture: Gesture,
+	pub gesture_drag: Vector2,
+	pub gesture_hold_duration: f32,
+	/// Every position the os cursor was moved to
+	pub warps: Vec<Vector2>,
+}
+
+impl Default for ScriptedInput {
+	fn default() -> Self {
+		Self {
+			screen_size: Vector2::new(800.0, 600.0),
+			dpi_scale: Vector2::one(),
+			is_highdpi: false,
+			is_focused: true,
+			keys_down: Vec::new(),
+			key_queue: VecDeque::new(),
+			char_queue: VecDeque::new(),
+			mouse_position: Vector2::zero(),
+			mouse_wheel: Vector2::zero(),
+			mouse_down: Vec::new(),
+			mouse_pressed: Vec::new(),
+			mouse_released: Vec::new(),
+			connected_gamepads: Vec::new(),
+			gamepad_buttons_down: Vec::new(),
+			gamepad_axes: Vec::new(),
+			touch_points: Vec::new(),
+			gesture: Gesture::GESTURE_NONE,
+			gesture_drag: Vector2::zero(),
+			gesture_hold_duration: 0.0,
+			warps: Vec::new(),
+		}
+	}
+}
+
+impl ScriptedInput {
+	/// Queue `key` as pressed this frame and hold it down
+	pub fn press_key(&mut self, key: KeyboardKey) {
+		self.key_queue.push_back(key as u32);
+		if !self.keys_down.contains(&key) {
+			self.keys_down.push(key);
+		}
+	}
+
+	pub fn release_key(&mut self, key: KeyboardKey) {
+		self.keys_down.retain(|&down| down != key);
+	}
+
+	pub fn press_mouse_button(&mut self, button: MouseButton) {
+		self.mouse_pressed.push(button);
+		self.mouse_down.push(button);
+	}
+
+	pub fn release_mouse_button(&mut self, button: MouseButton) {
+		self.mouse_released.push(button);
+		self.mouse_down.retain(|&down| down != button);
+	}
+
+	/// Forget this frame's presses and releases, like raylib does at the start of a frame
+	pub fn end_frame(&mut self) {
+		self.key_queue.clear();
+		self.char_queue.clear();
+		self.mouse_wheel = Vector2::zero();
+		self.mouse_pressed.clear();
+		self.mouse_released.clear();
+	}
+}
+
+impl InputSource for ScriptedInput {
+	fn screen_size(&self) -> Vector2 {
+		self.screen_size
+	}
+
+	fn window_scale_dpi(&self) -> Vector2 {
+		self.dpi_scale
+	}
+
+	fn is_window_highdpi(&self) -> bool {
+		self.is_highdpi
+	}
+
+	fn frame_time(&self) -> f32 {
+		1.0 / 60.0
+	}
+
+	fn is_window_focused(&self) -> bool {
+		self.is_focused
+	}
+
+	fn is_key_down(&self, key: KeyboardKey) -> bool {
+		self.keys_down.contains(&key)
+	}
+
+	fn is_key_pressed(&self, key: KeyboardKey) -> bool {
+		self.key_queue.contains(&(key as u32))
+	}
+
+	fn is_key_released(&self, _key: KeyboardKey) -> bool {
+		false
+	}
+
+	fn get_key_pressed(&mut self) -> Option<u32> {
+		self.key_queue.pop_front()
+	}
+
+	fn get_char_pressed(&mut self) -> Option<char> {
+		self.char_queue.pop_front()
+	}
+
+	fn mouse_position(&self) -> Vector2 {
+		self.mouse_position
+	}
+
+	fn mouse_wheel_move(&self) -> Vector2 {
+		self.mouse_wheel
+	}
+
+	fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+		self.mouse_down.contains(&button)
+	}
+
+	fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+		self.mouse_pressed.contains(&button)
+	}
+
+	fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+		self.mouse_released.contains(&button)
+	}
+
+	fn is_gamepad_available(&self, gamepad: i32) -> bool {
+		self.connected_gamepads.contains(&gamepad)
+	}
+
+	fn is_gamepad_button_down(&self, gamepad: i32, button: GamepadButton) -> bool {
+		self.gamepad_buttons_down.contains(&(gamepad, button))
+	}
+
+	fn is_gamepad_button_pressed(&self, _gamepad: i32, _button: GamepadButton) -> bool {
+		false
+	}
+
+	fn is_gamepad_button_released(&self, _gamepad: i32, _button: GamepadButton) -> bool {
+		false
+	}
+
+	fn gamepad_axis_movement(&self, gamepad: i32, axis: GamepadAxis) -> f32 {
+		self.gamepad_axes.iter()
+			.find(|&&(axis_gamepad, moved_axis, _)| axis_gamepad == gamepad && moved_axis == axis)
+			.map_or(0.0, |&(_, _, value)| value)
+	}
+
+	fn touch_point_count(&self) -> u32 {
+		self.touch_points.len() as u32
+	}
+
+	fn touch_position(&self, index: u32) -> Vector2 {
+		self.touch_points.get(index as usize).copied().unwrap_or_default()
+	}
+
+	fn gesture_detected(&self) -> Gesture {
+		self.gesture
+	}
+
+	fn gesture_drag_vector(&self) -> Vector2 {
+		self.gesture_drag
+	}
+
+	fn gesture_hold_duration(&self) -> f32 {
+		self.gesture_hold_duration
+	}
+
+	fn set_mouse_position(&mut self, position: Vector2) {
+		self.warps.push(position);
+	}
+}
\ No newline at end of file
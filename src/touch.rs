@@ -0,0 +1,140 @@
+use raylib::prelude::*;
+use crate::input::InputSource;
+
+/// How touch points and raylib gestures are turned into imgui input.
+///
+/// The imgui version this crate uses can't tag events as coming from a touchscreen,
+/// so touches are sent as plain mouse events
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TouchConfig {
+	/// Send the first touch point as the mouse position, with the left button held while it's down.
+	/// While drag scrolling or long presses are on too, the left button waits until the touch turns out to be neither,
+	/// so scrolling or right clicking doesn't also click
+	pub touch_as_mouse: bool,
+	/// Scroll when a touch is dragged. Dragging the full height of the screen scrolls this many wheel steps.
+	/// Mouse drags are left alone, even though raylib reports them as gestures too
+	pub drag_scroll_speed: Option<f32>,
+	/// Scroll when a pinch gesture is detected. Spreading the fingers a full screen apart scrolls this many wheel steps
+	pub pinch_scroll_speed: Option<f32>,
+	/// Right click after a touch is held still for this many seconds
+	pub long_press_right_click: Option<f32>,
+}
+
+impl TouchConfig {
+	/// Every mapping turned on, for apps that are mainly used on a touchscreen.
+	/// Taps click, drags scroll and long presses right click
+	pub fn touchscreen() -> Self {
+		Self {
+			touch_as_mouse: true,
+			drag_scroll_speed: Some(20.0),
+			pinch_scroll_speed: Some(20.0),
+			long_press_right_click: Some(0.5),
+		}
+	}
+}
+
+/// What the current touch turned into
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum TouchAction {
+	/// Nothing is touching the screen
+	#[default]
+	None,
+	/// Not decided yet, it could still become a tap, a drag or a long press
+	Pending,
+	/// Holding the left button, with [`TouchConfig::touch_as_mouse`]
+	LeftDown,
+	DragScroll,
+	/// Holding the right button
+	LongPress,
+}
+
+#[derive(Default)]
+pub(crate) struct TouchState {
+	action: TouchAction,
+	last_drag: Option<Vector2>,
+	last_pinch_distance: Option<f32>,
+}
+
+impl TouchState {
+	pub(crate) fn process<I: InputSource>(&mut self, config: &TouchConfig, io: &mut imgui::Io, input: &I) {
+		let is_touching = input.touch_point_count() > 0;
+		let gesture = input.gesture_detected();
+
+		if config.touch_as_mouse && is_touching {
+			let position = input.touch_position(0);
+			io.add_mouse_pos_event([position.x, position.y]);
+		}
+
+		if is_touching && self.action == TouchAction::None {
+			let can_change = config.drag_scroll_speed.is_some() || config.long_press_right_click.is_some();
+
+			self.action = if config.touch_as_mouse && !can_change {
+				io.add_mouse_button_event(imgui::MouseButton::Left, true);
+				TouchAction::LeftDown
+			} else {
+				TouchAction::Pending
+			};
+		}
+
+		if self.action == TouchAction::Pending && gesture == Gesture::GESTURE_DRAG {
+			if config.drag_scroll_speed.is_some() {
+				self.action = TouchAction::DragScroll;
+			} else if config.touch_as_mouse {
+				// Without drag scrolling, dragging works like it does with a mouse
+				io.add_mouse_button_event(imgui::MouseButton::Left, true);
+				self.action = TouchAction::LeftDown;
+			}
+		}
+
+		// Raylib's gesture vectors are normalized to the screen size
+		if let Some(speed) = config.drag_scroll_speed {
+			if self.action == TouchAction::DragScroll && gesture == Gesture::GESTURE_DRAG {
+				// The drag vector is measured from where the touch started, so scroll by how much it changed since last frame
+				let drag = input.gesture_drag_vector();
+				if let Some(last_drag) = self.last_drag {
+					io.add_mouse_wheel_event([(drag.x - last_drag.x) * speed, (drag.y - last_drag.y) * speed]);
+				}
+
+				self.last_drag = Some(drag);
+			} else {
+				self.last_drag = None;
+			}
+		}
+
+		if let Some(speed) = config.pinch_scroll_speed {
+			if gesture == Gesture::GESTURE_PINCH_IN || gesture == Gesture::GESTURE_PINCH_OUT {
+				// The pinch vector goes between the two fingers, so scroll by how much it changed since last frame
+				let distance = input.gesture_pinch_vector().length();
+				if let Some(last_distance) = self.last_pinch_distance {
+					io.add_mouse_wheel_event([0.0, (distance - last_distance) * speed]);
+				}
+
+				self.last_pinch_distance = Some(distance);
+			} else {
+				self.last_pinch_distance = None;
+			}
+		}
+
+		if let Some(hold_time) = config.long_press_right_click {
+			if self.action == TouchAction::Pending && gesture == Gesture::GESTURE_HOLD && input.gesture_hold_duration() >= hold_time {
+				io.add_mouse_button_event(imgui::MouseButton::Right, true);
+				self.action = TouchAction::LongPress;
+			}
+		}
+
+		if !is_touching {
+			match self.action {
+				// A touch that let go before turning into anything else was a tap
+				TouchAction::Pending if config.touch_as_mouse => {
+					io.add_mouse_button_event(imgui::MouseButton::Left, true);
+					io.add_mouse_button_event(imgui::MouseButton::Left, false);
+				}
+				TouchAction::LeftDown => io.add_mouse_button_event(imgui::MouseButton::Left, false),
+				TouchAction::LongPress => io.add_mouse_button_event(imgui::MouseButton::Right, false),
+				_ => {}
+			}
+
+			self.action = TouchAction::None;
+		}
+	}
+}
\ No newline at end of file